@@ -0,0 +1,168 @@
+//! Grouping of split packages by their package base.
+//!
+//! A single PKGBUILD can build several split packages, all sharing the
+//! package base that is cloned and built. A [`Base`] collects the packages
+//! of one base, records which of them were requested, and merges their
+//! dependencies, so that each base is fetched and built exactly once no
+//! matter how many of its packages are wanted.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aur::base::Base;
+//! use aur::model::InfoResult;
+//!
+//! fn print_builds(packages: Vec<InfoResult>) {
+//!     for base in Base::group(packages, &["foo", "foo-docs"]) {
+//!         let requested = base.requested()
+//!             .iter()
+//!             .map(|package| &package.name[..])
+//!             .collect::<Vec<_>>();
+//!
+//!         println!("{} {}: {}", base.name(), base.version(), requested.join(" "));
+//!     }
+//! }
+//! ```
+//!
+//! [`Base`]: struct.Base.html
+
+use dependency::Dependency;
+use model::InfoResult;
+use provider::Candidate;
+
+/// The packages of a package base.
+#[derive(Clone, Debug)]
+pub struct Base {
+    id: u64,
+    name: String,
+    packages: Vec<InfoResult>,
+    requested: Vec<bool>,
+}
+
+impl Base {
+    /// Groups packages by package base, in the order each base is first
+    /// encountered, keeping the packages of a base in the order given.
+    ///
+    /// A package is marked as requested if its name is among the requested
+    /// names. A package given more than once is only kept once.
+    pub fn group<I, S>(packages: I, requested: &[S]) -> Vec<Base>
+        where I: IntoIterator<Item = InfoResult>,
+              S: AsRef<str> {
+        let mut bases = Vec::<Base>::new();
+
+        for package in packages {
+            let is_requested = requested.iter().any(|name| name.as_ref() == package.name);
+            let position = bases.iter().position(|base| base.name == package.package_base);
+
+            match position {
+                Some(idx) => bases[idx].add(package, is_requested),
+                None => bases.push(Base {
+                    id: package.package_base_id,
+                    name: package.package_base.clone(),
+                    packages: vec![package],
+                    requested: vec![is_requested],
+                }),
+            }
+        }
+
+        bases
+    }
+
+    /// Returns the ID of the package base.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the name of the package base, which is what is cloned and
+    /// built.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the version of the package base, which all of its packages
+    /// share.
+    pub fn version(&self) -> &str {
+        &self.packages[0].version
+    }
+
+    /// Returns the packages of the base, both requested and not.
+    pub fn packages(&self) -> &[InfoResult] {
+        &self.packages
+    }
+
+    /// Returns the packages of the base that were requested.
+    pub fn requested(&self) -> Vec<&InfoResult> {
+        self.packages
+            .iter()
+            .zip(&self.requested)
+            .filter(|&(_, &requested)| requested)
+            .map(|(package, _)| package)
+            .collect()
+    }
+
+    /// Whether the package with the given name is part of the base and was
+    /// requested.
+    pub fn is_requested(&self, name: &str) -> bool {
+        self.packages
+            .iter()
+            .zip(&self.requested)
+            .any(|(package, &requested)| requested && package.name == name)
+    }
+
+    /// Returns the dependencies of every package of the base.
+    ///
+    /// Dependencies are kept in the order they are first encountered, each
+    /// only once, and dependencies satisfied by a package of the base itself
+    /// are left out.
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        self.merge(InfoResult::parsed_dependencies)
+    }
+
+    /// Returns the make dependencies of every package of the base.
+    ///
+    /// The dependencies are merged like those of [`dependencies`].
+    ///
+    /// [`dependencies`]: #method.dependencies
+    pub fn make_depends(&self) -> Vec<Dependency> {
+        self.merge(InfoResult::parsed_make_depends)
+    }
+
+    /// Returns the check dependencies of every package of the base.
+    ///
+    /// The dependencies are merged like those of [`dependencies`].
+    ///
+    /// [`dependencies`]: #method.dependencies
+    pub fn check_depends(&self) -> Vec<Dependency> {
+        self.merge(InfoResult::parsed_check_depends)
+    }
+
+    /// Merges a kind of dependency over every package of the base.
+    fn merge<F>(&self, dependencies: F) -> Vec<Dependency>
+        where F: Fn(&InfoResult) -> Vec<Dependency> {
+        let mut merged = Vec::new();
+
+        for dependency in self.packages.iter().flat_map(&dependencies) {
+            let internal = self.packages
+                .iter()
+                .any(|package| Candidate::new(package, &dependency).is_some());
+
+            if !internal && !merged.contains(&dependency) {
+                merged.push(dependency);
+            }
+        }
+
+        merged
+    }
+
+    /// Adds a package of the base, or marks it as requested if it was
+    /// already added.
+    fn add(&mut self, package: InfoResult, requested: bool) {
+        match self.packages.iter().position(|existing| existing.name == package.name) {
+            Some(idx) => self.requested[idx] |= requested,
+            None => {
+                self.packages.push(package);
+                self.requested.push(requested);
+            },
+        }
+    }
+}
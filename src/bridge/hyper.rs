@@ -6,17 +6,27 @@
 //!
 //! [`AurRequester`]: trait.AurRequester.html
 
-use constants::API_URI;
-use futures::{Future, Stream, future};
+use endpoint::Endpoint;
+use futures::sync::{mpsc, oneshot};
+use futures::{Future, Sink, Stream, future, stream};
 use hyper::body::Body;
+use hyper::Chunk;
 use hyper::client::connect::Connect;
 use hyper::client::Client as HyperClient;
 use hyper::{Request, Uri};
-use model::{InfoResult, Search, SearchResult};
+use instrument::Operation;
+use model::{InfoResult, ResponseType, Search, SearchBy, SearchResult};
+use serde::de::DeserializeOwned;
 use serde_json;
+use snapshot;
 use std::fmt::{Display, Write};
+use std::fs::{self, File};
+use std::io::{Error as IoError, ErrorKind as IoErrorKind, Write as IoWrite};
+use std::path::{Path, PathBuf};
+use std::result::Result as StdResult;
 use std::str::FromStr;
-use Error;
+use std::thread;
+use {Error, Result};
 
 macro_rules! ftry {
     ($code:expr) => {
@@ -27,6 +37,10 @@ macro_rules! ftry {
     }
 }
 
+/// The number of chunks of a snapshot's body buffered for the thread writing
+/// it to disk.
+const SNAPSHOT_BUFFER: usize = 16;
+
 /// Trait which defines the methods necessary to interact with the service.
 ///
 /// # Examples
@@ -77,13 +91,34 @@ pub trait AurRequester {
     /// Resolves to [`Error::Json`] if there was an error deserializing the
     /// response body.
     ///
+    /// Resolves to [`Error::Rpc`] if the API responded with an error.
+    ///
     /// Resolves to [`Error::Uri`] if there was an error parsing the Uri.
     ///
+    /// Resolves to [`Error::UnexpectedResponseType`] if the API responded with a
+    /// response not answering the request.
+    ///
     /// [`Error::Fmt`]: ../../enum.Error.html#variant.Fmt
     /// [`Error::Hyper`]: ../../enum.Error.html#variant.Hyper
     /// [`Error::Json`]: ../../enum.Error.html#variant.Json
+    /// [`Error::Rpc`]: ../../enum.Error.html#variant.Rpc
     /// [`Error::Uri`]: ../../enum.Error.html#variant.Uri
+    /// [`Error::UnexpectedResponseType`]: ../../enum.Error.html#variant.UnexpectedResponseType
     fn aur_info<T: Display>(&self, packages: &[T])
+        -> Box<Future<Item = Search<InfoResult>, Error = Error> + Send> {
+        self.aur_info_at(&Endpoint::default(), packages)
+    }
+
+    /// Retrieves information about one or more packages from the RPC
+    /// interface of the given [`Endpoint`].
+    ///
+    /// # Errors
+    ///
+    /// Resolves the same errors as [`aur_info`].
+    ///
+    /// [`Endpoint`]: ../../struct.Endpoint.html
+    /// [`aur_info`]: #method.aur_info
+    fn aur_info_at<T: Display>(&self, endpoint: &Endpoint, packages: &[T])
         -> Box<Future<Item = Search<InfoResult>, Error = Error> + Send>;
 
     /// Searches for packages by a query, optionally filtering by maintainer
@@ -123,23 +158,197 @@ pub trait AurRequester {
     /// Resolves to [`Error::Json`] if there was an error deserializing the
     /// response body.
     ///
+    /// Resolves to [`Error::Rpc`] if the API responded with an error.
+    ///
     /// Resolves to [`Error::Uri`] if there was an error parsing the Uri.
     ///
+    /// Resolves to [`Error::UnexpectedResponseType`] if the API responded with a
+    /// response not answering the request.
+    ///
     /// [`Error::Fmt`]: ../../enum.Error.html#variant.Fmt
     /// [`Error::Hyper`]: ../../enum.Error.html#variant.Hyper
     /// [`Error::Json`]: ../../enum.Error.html#variant.Json
+    /// [`Error::Rpc`]: ../../enum.Error.html#variant.Rpc
     /// [`Error::Uri`]: ../../enum.Error.html#variant.Uri
+    /// [`Error::UnexpectedResponseType`]: ../../enum.Error.html#variant.UnexpectedResponseType
     fn aur_search(&self, query: Option<&str>, maintainer: Option<&str>)
+        -> Box<Future<Item = Search<SearchResult>, Error = Error> + Send> {
+        self.aur_search_at(&Endpoint::default(), query, maintainer)
+    }
+
+    /// Searches for packages by a query using the RPC interface of the given
+    /// [`Endpoint`], optionally filtering by maintainer name.
+    ///
+    /// # Errors
+    ///
+    /// Resolves the same errors as [`aur_search`].
+    ///
+    /// [`Endpoint`]: ../../struct.Endpoint.html
+    /// [`aur_search`]: #method.aur_search
+    fn aur_search_at(&self, endpoint: &Endpoint, query: Option<&str>, maintainer: Option<&str>)
         -> Box<Future<Item = Search<SearchResult>, Error = Error> + Send>;
+
+    /// Searches for packages whose given field matches a query, such as the
+    /// packages providing `"java-runtime"`.
+    ///
+    /// # Examples
+    ///
+    /// Ensure that at least one package provides `"java-runtime"`:
+    ///
+    /// ```rust,ignore
+    /// extern crate aur;
+    /// extern crate hyper;
+    /// extern crate hyper_tls;
+    /// extern crate tokio;
+    ///
+    /// use aur::bridge::hyper::AurRequester;
+    /// use aur::model::SearchBy;
+    /// use hyper::Client;
+    /// use hyper_tls::HttpsConnector;
+    ///
+    /// let connector = HttpsConnector::new(4)?;
+    /// let client = Client::builder().build(connector);
+    ///
+    /// let done = client.aur_search_by("java-runtime", SearchBy::Provides).map(|search| {
+    ///     assert!(search.result_count >= 1);
+    /// }).map_err(|_| ());
+    ///
+    /// tokio::run(done);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Resolves to [`Error::Fmt`] if there was an error formatting the URI.
+    ///
+    /// Resolves to [`Error::Hyper`] if there was an error sending the request.
+    ///
+    /// Resolves to [`Error::Json`] if there was an error deserializing the
+    /// response body.
+    ///
+    /// Resolves to [`Error::Rpc`] if the API responded with an error.
+    ///
+    /// Resolves to [`Error::Uri`] if there was an error parsing the Uri.
+    ///
+    /// Resolves to [`Error::UnexpectedResponseType`] if the API responded with a
+    /// response not answering the request.
+    ///
+    /// [`Error::Fmt`]: ../../enum.Error.html#variant.Fmt
+    /// [`Error::Hyper`]: ../../enum.Error.html#variant.Hyper
+    /// [`Error::Json`]: ../../enum.Error.html#variant.Json
+    /// [`Error::Rpc`]: ../../enum.Error.html#variant.Rpc
+    /// [`Error::Uri`]: ../../enum.Error.html#variant.Uri
+    /// [`Error::UnexpectedResponseType`]: ../../enum.Error.html#variant.UnexpectedResponseType
+    fn aur_search_by(&self, query: &str, by: SearchBy)
+        -> Box<Future<Item = Search<SearchResult>, Error = Error> + Send> {
+        self.aur_search_by_at(&Endpoint::default(), query, by)
+    }
+
+    /// Searches for packages whose given field matches a query using the RPC
+    /// interface of the given [`Endpoint`].
+    ///
+    /// # Errors
+    ///
+    /// Resolves the same errors as [`aur_search_by`].
+    ///
+    /// [`Endpoint`]: ../../struct.Endpoint.html
+    /// [`aur_search_by`]: #method.aur_search_by
+    fn aur_search_by_at(&self, endpoint: &Endpoint, query: &str, by: SearchBy)
+        -> Box<Future<Item = Search<SearchResult>, Error = Error> + Send>;
+
+    /// Downloads the snapshot tarball of a package base into a destination
+    /// directory and safely extracts it there, resolving to the path of the
+    /// extracted directory.
+    ///
+    /// The tarball is saved to `<destination>/<package_base>.tar.gz` and kept
+    /// after extraction. Refer to the [`snapshot`] module for the entries
+    /// that are rejected during extraction.
+    ///
+    /// The response body is streamed to a separate thread, which writes each
+    /// chunk to the tarball as it arrives and extracts the tarball once the
+    /// body is complete, so that the blocking filesystem work does not run on
+    /// the thread polling the future.
+    ///
+    /// # Examples
+    ///
+    /// Download and extract the `"rust-nightly"` package base into the
+    /// `"build"` directory:
+    ///
+    /// ```rust,ignore
+    /// extern crate aur;
+    /// extern crate hyper;
+    /// extern crate hyper_tls;
+    /// extern crate tokio;
+    ///
+    /// use aur::bridge::hyper::AurRequester;
+    /// use hyper::Client;
+    /// use hyper_tls::HttpsConnector;
+    ///
+    /// let connector = HttpsConnector::new(4)?;
+    /// let client = Client::builder().build(connector);
+    ///
+    /// let done = client.aur_download_snapshot("rust-nightly", "build").map(|path| {
+    ///     assert!(path.join("PKGBUILD").exists());
+    /// }).map_err(|_| ());
+    ///
+    /// tokio::run(done);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Resolves to [`Error::Hyper`] if there was an error sending the request
+    /// or receiving the response body.
+    ///
+    /// Resolves to [`Error::InvalidPackageBase`] if the package base name can
+    /// not be used as a file name, such as one containing a `/`.
+    ///
+    /// Resolves to [`Error::Io`] if there was an error writing or extracting
+    /// the tarball.
+    ///
+    /// Resolves to [`Error::Status`] if the response status code was not
+    /// successful, such as for a package base that does not exist.
+    ///
+    /// Resolves to [`Error::UnsafeArchiveEntry`] if the tarball contained an
+    /// unsafe entry.
+    ///
+    /// Resolves to [`Error::Uri`] if there was an error parsing the Uri.
+    ///
+    /// [`Error::Hyper`]: ../../enum.Error.html#variant.Hyper
+    /// [`Error::InvalidPackageBase`]: ../../enum.Error.html#variant.InvalidPackageBase
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    /// [`Error::Status`]: ../../enum.Error.html#variant.Status
+    /// [`Error::UnsafeArchiveEntry`]: ../../enum.Error.html#variant.UnsafeArchiveEntry
+    /// [`Error::Uri`]: ../../enum.Error.html#variant.Uri
+    /// [`snapshot`]: ../../snapshot/index.html
+    fn aur_download_snapshot<P: AsRef<Path>>(&self, package_base: &str, destination: P)
+        -> Box<Future<Item = PathBuf, Error = Error> + Send> {
+        self.aur_download_snapshot_at(&Endpoint::default(), package_base, destination)
+    }
+
+    /// Downloads the snapshot tarball of a package base from the given
+    /// [`Endpoint`] into a destination directory and safely extracts it
+    /// there.
+    ///
+    /// # Errors
+    ///
+    /// Resolves the same errors as [`aur_download_snapshot`].
+    ///
+    /// [`Endpoint`]: ../../struct.Endpoint.html
+    /// [`aur_download_snapshot`]: #method.aur_download_snapshot
+    fn aur_download_snapshot_at<P: AsRef<Path>>(
+        &self,
+        endpoint: &Endpoint,
+        package_base: &str,
+        destination: P,
+    ) -> Box<Future<Item = PathBuf, Error = Error> + Send>;
 }
 
 impl<C> AurRequester for HyperClient<C, Body>
     where C: Connect + Sync + 'static,
           C::Future: 'static,
           C::Transport: 'static {
-    fn aur_info<T: Display>(&self, packages: &[T])
+    fn aur_info_at<T: Display>(&self, endpoint: &Endpoint, packages: &[T])
         -> Box<Future<Item = Search<InfoResult>, Error = Error> + Send + 'static> {
-        let mut url = format!("{}&type=info", API_URI);
+        let mut url = format!("{}&type=info", endpoint.rpc_url());
 
         for package in packages {
             if let Err(why) = write!(url, "&arg[]={}", package) {
@@ -147,21 +356,14 @@ impl<C> AurRequester for HyperClient<C, Body>
             }
         }
 
-        let c = &url;
-        let uri = ftry!(Uri::from_str(c));
+        let operation = Operation::new("info", packages.len(), &url);
 
-        let mut request = Request::get(uri);
-        let req = ftry!(request.body(Body::empty()));
-
-        Box::new(self.request(req)
-            .and_then(|res| res.into_body().concat2())
-            .map_err(From::from)
-            .and_then(|body| serde_json::from_slice(&body).map_err(From::from)))
+        send(self, &url, ResponseType::Info, operation)
     }
 
-    fn aur_search(&self, query: Option<&str>, maintainer: Option<&str>)
+    fn aur_search_at(&self, endpoint: &Endpoint, query: Option<&str>, maintainer: Option<&str>)
         -> Box<Future<Item = Search<SearchResult>, Error = Error> + Send + 'static> {
-        let mut url = format!("{}&type=search", API_URI);
+        let mut url = format!("{}&type=search", endpoint.rpc_url());
 
         if let Some(query) = query {
             url.push_str("&arg=");
@@ -173,15 +375,164 @@ impl<C> AurRequester for HyperClient<C, Body>
             url.push_str(maintainer);
         }
 
-        let c = &url;
-        let uri = ftry!(Uri::from_str(c));
+        let arg_count = query.iter().chain(maintainer.iter()).count();
+        let operation = Operation::new("search", arg_count, &url);
+
+        send(self, &url, ResponseType::Search, operation)
+    }
+
+    fn aur_search_by_at(&self, endpoint: &Endpoint, query: &str, by: SearchBy)
+        -> Box<Future<Item = Search<SearchResult>, Error = Error> + Send + 'static> {
+        let url = format!("{}&type=search&by={}&arg={}", endpoint.rpc_url(), by.as_str(), query);
+
+        let operation = Operation::new("search", 1, &url);
+
+        send(self, &url, ResponseType::Search, operation)
+    }
+
+    fn aur_download_snapshot_at<P: AsRef<Path>>(
+        &self,
+        endpoint: &Endpoint,
+        package_base: &str,
+        destination: P,
+    ) -> Box<Future<Item = PathBuf, Error = Error> + Send + 'static> {
+        let destination = destination.as_ref().to_owned();
+        let tarball = ftry!(snapshot::tarball_path(&destination, package_base));
+        let directory = destination.join(package_base);
+
+        let url = endpoint.snapshot_url(package_base);
+        let operation = Operation::new("snapshot", 1, &url);
+
+        let uri = ftry!(Uri::from_str(&url));
+        let req = ftry!(Request::get(uri).body(Body::empty()));
 
-        let mut request = Request::get(uri);
-        let req = ftry!(request.body(Body::empty()));
+        let on_poll = operation.clone();
+        let on_response = operation.clone();
+        let on_body = operation.clone();
 
-        Box::new(self.request(req)
-            .and_then(|res| res.into_body().concat2())
+        Box::new(on_poll.instrument(self.request(req)
             .map_err(From::from)
-            .and_then(|body| serde_json::from_slice(&body).map_err(From::from)))
+            .and_then(move |res| {
+                let status = res.status().as_u16();
+                on_response.status(status);
+
+                if !res.status().is_success() {
+                    return Err(Error::Status(status));
+                }
+
+                Ok(res.into_body())
+            })
+            .and_then(move |body| {
+                // Writing and extracting the tarball blocks, so it is done on
+                // its own thread, which writes the chunks of the body as they
+                // arrive. The end of the body is sent as `None`, so that a
+                // body cut short by an error is never extracted.
+                let (chunks, received) = mpsc::channel(SNAPSHOT_BUFFER);
+                let (sender, written) = oneshot::channel();
+
+                thread::spawn(move || {
+                    let result = write_snapshot(received.wait(), &tarball, &destination);
+                    let _ = sender.send(result);
+                });
+
+                // The chunks stop being received if writing fails, in which
+                // case the error of the writing thread is resolved instead.
+                body.map(Some)
+                    .map_err(|why| Some(Error::from(why)))
+                    .chain(stream::once(Ok(None)))
+                    .forward(chunks.sink_map_err(|_| None))
+                    .then(move |forwarded| written.then(move |written| {
+                        match (forwarded, written) {
+                            (Err(Some(why)), _) => Err(why),
+                            (_, Ok(result)) => result,
+                            (_, Err(_)) => {
+                                Err(Error::Io(IoError::other("snapshot extraction panicked")))
+                            },
+                        }
+                    }))
+            })
+            .map(move |size| {
+                on_body.response_size(size);
+
+                directory
+            })
+            .then(move |result: Result<PathBuf>| {
+                operation.finish(result.as_ref().map(|_| 1));
+
+                result
+            })))
+    }
+}
+
+/// Writes the chunks of a snapshot tarball as they are received, and then
+/// extracts it into the destination, returning the size of the tarball.
+///
+/// The tarball is only extracted once `None` marks the end of the body.
+fn write_snapshot<I>(chunks: I, tarball: &Path, destination: &Path) -> Result<usize>
+    where I: Iterator<Item = StdResult<Option<Chunk>, ()>> {
+    fs::create_dir_all(destination)?;
+    let mut file = File::create(tarball)?;
+    let mut size = 0;
+
+    for chunk in chunks {
+        match chunk {
+            Ok(Some(chunk)) => {
+                file.write_all(&chunk)?;
+                size += chunk.len();
+            },
+            Ok(None) => {
+                drop(file);
+                snapshot::extract_file(tarball, destination)?;
+
+                return Ok(size);
+            },
+            Err(()) => break,
+        }
     }
+
+    Err(Error::Io(IoError::new(IoErrorKind::UnexpectedEof, "snapshot body ended early")))
+}
+
+fn send<C, T>(
+    client: &HyperClient<C, Body>,
+    url: &str,
+    request_type: ResponseType,
+    operation: Operation,
+)
+    -> Box<Future<Item = Search<T>, Error = Error> + Send + 'static>
+    where C: Connect + Sync + 'static,
+          C::Future: 'static,
+          C::Transport: 'static,
+          T: DeserializeOwned + Send + Sync + 'static {
+    let uri = ftry!(Uri::from_str(url));
+
+    let mut request = Request::get(uri);
+    let req = ftry!(request.body(Body::empty()));
+
+    let on_poll = operation.clone();
+    let on_response = operation.clone();
+    let on_body = operation.clone();
+
+    Box::new(on_poll.instrument(client.request(req)
+        .and_then(move |res| {
+            on_response.status(res.status().as_u16());
+
+            res.into_body().concat2()
+        })
+        .map_err(From::from)
+        .and_then(move |body| {
+            on_body.response_size(body.len());
+
+            serde_json::from_slice::<Search<T>>(&body).map_err(|why| {
+                on_body.deserialize_error(&why);
+
+                From::from(why)
+            })
+        })
+        .and_then(move |search| search.verify(request_type))
+        .then(move |result: Result<Search<T>>| {
+            operation.finish(result.as_ref().map(|search| search.result_count));
+
+            result
+        })))
 }
@@ -6,10 +6,14 @@
 //!
 //! [`AurRequester`]: trait.AurRequester.html
 
-use constants::API_URI;
-use model::{InfoResult, Search, SearchResult};
+use endpoint::Endpoint;
+use instrument::Operation;
+use model::{InfoResult, ResponseType, Search, SearchBy, SearchResult};
+use snapshot;
 use std::fmt::{Display, Write};
-use std::io::Read;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 use reqwest::{Client as ReqwestClient, RequestBuilder, StatusCode, Url};
 use serde::de::DeserializeOwned;
 use serde_json;
@@ -74,16 +78,37 @@ pub trait AurRequester {
     /// Returns [`Error::ReqwestParse`] if there was a parsing issue with the
     /// response.
     ///
+    /// Returns [`Error::Rpc`] if the API responded with an error.
+    ///
     /// Returns [`Error::Uri`] if there was an error parsing the Uri.
     ///
+    /// Returns [`Error::UnexpectedResponseType`] if the API responded with a
+    /// response not answering the request.
+    ///
     /// [`Error::Fmt`]: ../../enum.Error.html#variant.Fmt
     /// [`Error::Json`]: ../../enum.Error.html#variant.Json
     /// [`Error::Reqwest`]: ../../enum.Error.html#variant.Reqwest
     /// [`Error::ReqwestBad`]: ../../enum.Error.html#variant.ReqwestBad
     /// [`Error::ReqwestInvalid`]: ../../enum.Error.html#variant.ReqwestInvalid
     /// [`Error::ReqwestParse`]: ../../enum.Error.html#variant.ReqwestParse
+    /// [`Error::Rpc`]: ../../enum.Error.html#variant.Rpc
     /// [`Error::Uri`]: ../../enum.Error.html#variant.Uri
+    /// [`Error::UnexpectedResponseType`]: ../../enum.Error.html#variant.UnexpectedResponseType
     fn aur_info<T: Display>(&self, packages: &[T])
+        -> Result<Search<InfoResult>> {
+        self.aur_info_at(&Endpoint::default(), packages)
+    }
+
+    /// Retrieves information about one or more packages from the RPC
+    /// interface of the given [`Endpoint`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`aur_info`].
+    ///
+    /// [`Endpoint`]: ../../struct.Endpoint.html
+    /// [`aur_info`]: #method.aur_info
+    fn aur_info_at<T: Display>(&self, endpoint: &Endpoint, packages: &[T])
         -> Result<Search<InfoResult>>;
 
     /// Searches for packages by a query, optionally filtering by maintainer
@@ -134,36 +159,224 @@ pub trait AurRequester {
     /// Returns [`Error::ReqwestParse`] if there was a parsing issue with the
     /// response.
     ///
+    /// Returns [`Error::Rpc`] if the API responded with an error.
+    ///
     /// Returns [`Error::Uri`] if there was an error parsing the Uri.
     ///
+    /// Returns [`Error::UnexpectedResponseType`] if the API responded with a
+    /// response not answering the request.
+    ///
     /// [`Error::Fmt`]: ../../enum.Error.html#variant.Fmt
     /// [`Error::Json`]: ../../enum.Error.html#variant.Json
     /// [`Error::Reqwest`]: ../../enum.Error.html#variant.Reqwest
     /// [`Error::ReqwestBad`]: ../../enum.Error.html#variant.ReqwestBad
     /// [`Error::ReqwestInvalid`]: ../../enum.Error.html#variant.ReqwestInvalid
     /// [`Error::ReqwestParse`]: ../../enum.Error.html#variant.ReqwestParse
+    /// [`Error::Rpc`]: ../../enum.Error.html#variant.Rpc
     /// [`Error::Uri`]: ../../enum.Error.html#variant.Uri
+    /// [`Error::UnexpectedResponseType`]: ../../enum.Error.html#variant.UnexpectedResponseType
     fn aur_search(&self, query: Option<&str>, maintainer: Option<&str>)
+        -> Result<Search<SearchResult>> {
+        self.aur_search_at(&Endpoint::default(), query, maintainer)
+    }
+
+    /// Searches for packages by a query using the RPC interface of the given
+    /// [`Endpoint`], optionally filtering by maintainer name.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`aur_search`].
+    ///
+    /// [`Endpoint`]: ../../struct.Endpoint.html
+    /// [`aur_search`]: #method.aur_search
+    fn aur_search_at(&self, endpoint: &Endpoint, query: Option<&str>, maintainer: Option<&str>)
+        -> Result<Search<SearchResult>>;
+
+    /// Searches for packages whose given field matches a query, such as the
+    /// packages providing `"java-runtime"`.
+    ///
+    /// # Examples
+    ///
+    /// Ensure that at least one package provides `"java-runtime"`:
+    ///
+    /// ```rust,no_run
+    /// extern crate aur;
+    /// extern crate reqwest;
+    ///
+    /// use aur::bridge::reqwest::AurRequester;
+    /// use aur::model::SearchBy;
+    /// use reqwest::Client;
+    ///
+    /// # use std::error::Error;
+    /// #
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// #
+    /// let client = Client::new();
+    ///
+    /// let search = client.aur_search_by("java-runtime", SearchBy::Provides)?;
+    ///
+    /// assert!(search.result_count >= 1);
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Fmt`] if there was an error formatting the URI.
+    ///
+    /// Returns [`Error::Json`] if there was an error deserializing the
+    /// response body.
+    ///
+    /// Returns [`Error::Reqwest`] if there was an error sending the request.
+    ///
+    /// Returns [`Error::ReqwestBad`] if the response status code was a 400.
+    ///
+    /// Returns [`Error::ReqwestInvalid`] if the request was invalid.
+    ///
+    /// Returns [`Error::ReqwestParse`] if there was a parsing issue with the
+    /// response.
+    ///
+    /// Returns [`Error::Rpc`] if the API responded with an error.
+    ///
+    /// Returns [`Error::Uri`] if there was an error parsing the Uri.
+    ///
+    /// Returns [`Error::UnexpectedResponseType`] if the API responded with a
+    /// response not answering the request.
+    ///
+    /// [`Error::Fmt`]: ../../enum.Error.html#variant.Fmt
+    /// [`Error::Json`]: ../../enum.Error.html#variant.Json
+    /// [`Error::Reqwest`]: ../../enum.Error.html#variant.Reqwest
+    /// [`Error::ReqwestBad`]: ../../enum.Error.html#variant.ReqwestBad
+    /// [`Error::ReqwestInvalid`]: ../../enum.Error.html#variant.ReqwestInvalid
+    /// [`Error::ReqwestParse`]: ../../enum.Error.html#variant.ReqwestParse
+    /// [`Error::Rpc`]: ../../enum.Error.html#variant.Rpc
+    /// [`Error::Uri`]: ../../enum.Error.html#variant.Uri
+    /// [`Error::UnexpectedResponseType`]: ../../enum.Error.html#variant.UnexpectedResponseType
+    fn aur_search_by(&self, query: &str, by: SearchBy)
+        -> Result<Search<SearchResult>> {
+        self.aur_search_by_at(&Endpoint::default(), query, by)
+    }
+
+    /// Searches for packages whose given field matches a query using the RPC
+    /// interface of the given [`Endpoint`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`aur_search_by`].
+    ///
+    /// [`Endpoint`]: ../../struct.Endpoint.html
+    /// [`aur_search_by`]: #method.aur_search_by
+    fn aur_search_by_at(&self, endpoint: &Endpoint, query: &str, by: SearchBy)
         -> Result<Search<SearchResult>>;
+
+    /// Downloads the snapshot tarball of a package base into a destination
+    /// directory and safely extracts it there, returning the path of the
+    /// extracted directory.
+    ///
+    /// The tarball is streamed to `<destination>/<package_base>.tar.gz` and
+    /// kept after extraction. Refer to the [`snapshot`] module for the
+    /// entries that are rejected during extraction.
+    ///
+    /// # Examples
+    ///
+    /// Download and extract the `"rust-nightly"` package base into the
+    /// `"build"` directory:
+    ///
+    /// ```rust,no_run
+    /// extern crate aur;
+    /// extern crate reqwest;
+    ///
+    /// use aur::bridge::reqwest::AurRequester;
+    /// use reqwest::Client;
+    ///
+    /// # use std::error::Error;
+    /// #
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// #
+    /// let client = Client::new();
+    ///
+    /// let path = client.aur_download_snapshot("rust-nightly", "build")?;
+    ///
+    /// assert!(path.join("PKGBUILD").exists());
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPackageBase`] if the package base name can not
+    /// be used as a file name, such as one containing a `/`.
+    ///
+    /// Returns [`Error::Io`] if there was an error writing or extracting the
+    /// tarball.
+    ///
+    /// Returns [`Error::Reqwest`] if there was an error sending the request.
+    ///
+    /// Returns [`Error::ReqwestParse`] if there was an error parsing the URL.
+    ///
+    /// Returns [`Error::Status`] if the response status code was not
+    /// successful, such as for a package base that does not exist, as the
+    /// `hyper` bridge does.
+    ///
+    /// Returns [`Error::UnsafeArchiveEntry`] if the tarball contained an unsafe
+    /// entry.
+    ///
+    /// [`Error::InvalidPackageBase`]: ../../enum.Error.html#variant.InvalidPackageBase
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    /// [`Error::Reqwest`]: ../../enum.Error.html#variant.Reqwest
+    /// [`Error::ReqwestParse`]: ../../enum.Error.html#variant.ReqwestParse
+    /// [`Error::Status`]: ../../enum.Error.html#variant.Status
+    /// [`Error::UnsafeArchiveEntry`]: ../../enum.Error.html#variant.UnsafeArchiveEntry
+    /// [`snapshot`]: ../../snapshot/index.html
+    fn aur_download_snapshot<P: AsRef<Path>>(&self, package_base: &str, destination: P)
+        -> Result<PathBuf> {
+        self.aur_download_snapshot_at(&Endpoint::default(), package_base, destination)
+    }
+
+    /// Downloads the snapshot tarball of a package base from the given
+    /// [`Endpoint`] into a destination directory and safely extracts it
+    /// there.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`aur_download_snapshot`].
+    ///
+    /// [`Endpoint`]: ../../struct.Endpoint.html
+    /// [`aur_download_snapshot`]: #method.aur_download_snapshot
+    fn aur_download_snapshot_at<P: AsRef<Path>>(
+        &self,
+        endpoint: &Endpoint,
+        package_base: &str,
+        destination: P,
+    ) -> Result<PathBuf>;
 }
 
 impl AurRequester for ReqwestClient {
-    fn aur_info<T: Display>(&self, packages: &[T])
+    fn aur_info_at<T: Display>(&self, endpoint: &Endpoint, packages: &[T])
         -> Result<Search<InfoResult>> {
-        let mut url = format!("{}&type=info", API_URI);
+        let mut url = format!("{}&type=info", endpoint.rpc_url());
 
         for package in packages {
             write!(url, "&arg[]={}", package)?;
         }
 
+        let operation = Operation::new("info", packages.len(), &url);
         let uri = Url::parse(&url)?;
 
-        handle_request::<Search<InfoResult>>(&mut self.get(uri))
+        handle_request(&mut self.get(uri), ResponseType::Info, &operation)
     }
 
-    fn aur_search(&self, query: Option<&str>, maintainer: Option<&str>)
+    fn aur_search_at(&self, endpoint: &Endpoint, query: Option<&str>, maintainer: Option<&str>)
         -> Result<Search<SearchResult>> {
-        let mut url = format!("{}&type=search", API_URI);
+        let mut url = format!("{}&type=search", endpoint.rpc_url());
 
         if let Some(query) = query {
             url.push_str("&arg=");
@@ -175,14 +388,64 @@ impl AurRequester for ReqwestClient {
             url.push_str(maintainer);
         }
 
+        let arg_count = query.iter().chain(maintainer.iter()).count();
+        let operation = Operation::new("search", arg_count, &url);
+        let uri = Url::parse(&url)?;
+
+        handle_request(&mut self.get(uri), ResponseType::Search, &operation)
+    }
+
+    fn aur_search_by_at(&self, endpoint: &Endpoint, query: &str, by: SearchBy)
+        -> Result<Search<SearchResult>> {
+        let url = format!("{}&type=search&by={}&arg={}", endpoint.rpc_url(), by.as_str(), query);
+
+        let operation = Operation::new("search", 1, &url);
+        let uri = Url::parse(&url)?;
+
+        handle_request(&mut self.get(uri), ResponseType::Search, &operation)
+    }
+
+    fn aur_download_snapshot_at<P: AsRef<Path>>(
+        &self,
+        endpoint: &Endpoint,
+        package_base: &str,
+        destination: P,
+    ) -> Result<PathBuf> {
+        let url = endpoint.snapshot_url(package_base);
+        let operation = Operation::new("snapshot", 1, &url);
         let uri = Url::parse(&url)?;
 
-        handle_request::<Search<SearchResult>>(&mut self.get(uri))
+        let result = operation.in_scope(|| download(
+            &mut self.get(uri),
+            package_base,
+            destination.as_ref(),
+            &operation,
+        ));
+
+        operation.finish(result.as_ref().map(|_| 1));
+
+        result
     }
 }
 
-fn handle_request<T: DeserializeOwned>(request: &mut RequestBuilder) -> Result<T> {
-    let response = request.send()?;
+fn handle_request<T>(
+    request: &mut RequestBuilder,
+    request_type: ResponseType,
+    operation: &Operation,
+) -> Result<Search<T>> where T: DeserializeOwned + Send + Sync {
+    let result = operation.in_scope(|| send(request, operation))
+        .and_then(|search| search.verify(request_type));
+
+    operation.finish(result.as_ref().map(|search| search.result_count));
+
+    result
+}
+
+fn send<T>(request: &mut RequestBuilder, operation: &Operation)
+    -> Result<Search<T>> where T: DeserializeOwned + Send + Sync {
+    let mut response = request.send()?;
+
+    operation.status(response.status().as_u16());
 
     match response.status() {
         StatusCode::Ok => {},
@@ -192,10 +455,40 @@ fn handle_request<T: DeserializeOwned>(request: &mut RequestBuilder) -> Result<T
         _ => return Err(Error::ReqwestInvalid(Box::new(response))),
     }
 
-    from_reader(response)
+    let mut body = Vec::new();
+    response.read_to_end(&mut body)?;
+
+    operation.response_size(body.len());
+
+    serde_json::from_slice::<Search<T>>(&body).map_err(|why| {
+        operation.deserialize_error(&why);
+
+        From::from(why)
+    })
 }
 
-#[inline]
-fn from_reader<T: DeserializeOwned, U: Read>(reader: U) -> Result<T> {
-    serde_json::from_reader(reader).map_err(From::from)
+fn download(
+    request: &mut RequestBuilder,
+    package_base: &str,
+    destination: &Path,
+    operation: &Operation,
+) -> Result<PathBuf> {
+    let tarball = snapshot::tarball_path(destination, package_base)?;
+    let mut response = request.send()?;
+
+    let status = response.status().as_u16();
+    operation.status(status);
+
+    if !response.status().is_success() {
+        return Err(Error::Status(status));
+    }
+
+    fs::create_dir_all(destination)?;
+    let size = io::copy(&mut response, &mut File::create(&tarball)?)?;
+
+    operation.response_size(size as usize);
+
+    snapshot::extract_file(&tarball, destination)?;
+
+    Ok(destination.join(package_base))
 }
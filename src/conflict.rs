@@ -0,0 +1,237 @@
+//! Detection of conflicts within a planned set of packages.
+//!
+//! Before building, the packages planned to be installed are checked against
+//! each other and against the installed packages. A conflict is reported
+//! whenever a package's `conflicts` matches another package, either by name
+//! or by one of its `provides`, and whenever a package's `replaces` names
+//! another package. Version constraints are respected throughout.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aur::conflict::{self, InstalledPackage};
+//! use aur::model::InfoResult;
+//!
+//! fn print_conflicts(planned: &[InfoResult]) {
+//!     let mut installed = InstalledPackage::new("foo", "1.0-1");
+//!     installed.provides.push("libfoo.so=1-64".to_owned());
+//!
+//!     for conflict in conflict::analyze(planned, &[installed]) {
+//!         println!("{}", conflict);
+//!     }
+//! }
+//! ```
+
+use dependency::Dependency;
+use model::InfoResult;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use version::Version;
+
+/// A package that is installed, as far as its relations are concerned.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct InstalledPackage {
+    /// The packages that the package conflicts with.
+    pub conflicts: Vec<String>,
+    /// The name of the package.
+    pub name: String,
+    /// The packages that the package provides.
+    pub provides: Vec<String>,
+    /// The packages that the package replaces.
+    pub replaces: Vec<String>,
+    /// The installed version of the package.
+    pub version: String,
+}
+
+impl InstalledPackage {
+    /// Creates an installed package without any relations.
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, version: V) -> Self {
+        InstalledPackage {
+            name: name.into(),
+            version: version.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A package taking part in a conflict.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Member {
+    /// A package planned to be installed, containing its name.
+    Planned(String),
+    /// A package that is installed, containing its name.
+    Installed(String),
+}
+
+impl Member {
+    /// Returns the name of the package.
+    pub fn name(&self) -> &str {
+        match *self {
+            Member::Planned(ref name) | Member::Installed(ref name) => name,
+        }
+    }
+
+    /// Whether the package is installed rather than planned.
+    pub fn is_installed(&self) -> bool {
+        match *self {
+            Member::Planned(_) => false,
+            Member::Installed(_) => true,
+        }
+    }
+}
+
+impl Display for Member {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            Member::Planned(ref name) => f.write_str(name),
+            Member::Installed(ref name) => write!(f, "{} (installed)", name),
+        }
+    }
+}
+
+/// The rule that triggered a conflict.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Rule {
+    /// An entry of the package's `conflicts` matched the other package by
+    /// name and version.
+    Conflicts(Dependency),
+    /// An entry of the package's `conflicts` matched an entry of the other
+    /// package's `provides`, containing both entries.
+    ConflictsProvision(Dependency, Dependency),
+    /// An entry of the package's `replaces` matched the other package by
+    /// name and version.
+    Replaces(Dependency),
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            Rule::Conflicts(ref conflict) => write!(f, "conflicts {}", conflict),
+            Rule::ConflictsProvision(ref conflict, ref provision) => {
+                write!(f, "conflicts {}, provided as {}", conflict, provision)
+            },
+            Rule::Replaces(ref replaced) => write!(f, "replaces {}", replaced),
+        }
+    }
+}
+
+/// A conflict between two packages.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Conflict {
+    /// The package conflicting with the other package.
+    pub other: Member,
+    /// The package whose relation triggered the conflict.
+    pub package: Member,
+    /// The rule that triggered the conflict.
+    pub rule: Rule,
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} conflicts with {}: {}", self.package, self.other, self.rule)
+    }
+}
+
+/// The relations of a planned or installed package.
+struct Entry<'a> {
+    conflicts: Vec<Dependency>,
+    member: Member,
+    name: &'a str,
+    provides: Vec<Dependency>,
+    replaces: Vec<Dependency>,
+    version: Version,
+}
+
+impl<'a> Entry<'a> {
+    fn planned(package: &'a InfoResult) -> Self {
+        Entry {
+            conflicts: package.parsed_conflicts(),
+            member: Member::Planned(package.name.clone()),
+            name: &package.name,
+            provides: package.parsed_provides(),
+            replaces: package.parsed_replaces(),
+            version: Version::parse(&package.version),
+        }
+    }
+
+    fn installed(package: &'a InstalledPackage) -> Self {
+        Entry {
+            conflicts: parse(&package.conflicts),
+            member: Member::Installed(package.name.clone()),
+            name: &package.name,
+            provides: parse(&package.provides),
+            replaces: parse(&package.replaces),
+            version: Version::parse(&package.version),
+        }
+    }
+
+    /// Returns the rules of this entry matching another entry.
+    fn rules(&self, other: &Entry) -> Vec<Rule> {
+        let mut rules = Vec::new();
+
+        for conflict in &self.conflicts {
+            if conflict.is_satisfied_by(other.name, &other.version) {
+                rules.push(Rule::Conflicts(conflict.clone()));
+            } else if let Some(provision) = other.provision(conflict) {
+                rules.push(Rule::ConflictsProvision(conflict.clone(), provision.clone()));
+            }
+        }
+
+        for replaced in &self.replaces {
+            if replaced.is_satisfied_by(other.name, &other.version) {
+                rules.push(Rule::Replaces(replaced.clone()));
+            }
+        }
+
+        rules
+    }
+
+    /// Returns the entry of `provides` matching a dependency, if any.
+    fn provision(&self, dependency: &Dependency) -> Option<&Dependency> {
+        self.provides
+            .iter()
+            .find(|provision| dependency.is_satisfied_by_provision(provision))
+    }
+}
+
+/// Finds the conflicts among planned packages and between planned and
+/// installed packages.
+///
+/// Both sides of every pair are checked, so a conflict declared by an
+/// installed package against a planned one is reported as well. An
+/// installed package with the same name as a planned one is considered to be
+/// upgraded by it and is not checked against it.
+///
+/// Conflicts are returned in the order of the planned packages.
+pub fn analyze<'a, I>(planned: I, installed: &[InstalledPackage]) -> Vec<Conflict>
+    where I: IntoIterator<Item = &'a InfoResult> {
+    let planned = planned.into_iter().map(Entry::planned).collect::<Vec<_>>();
+    let installed = installed
+        .iter()
+        .filter(|package| planned.iter().all(|entry| entry.name != package.name))
+        .map(Entry::installed)
+        .collect::<Vec<_>>();
+    let mut conflicts = Vec::new();
+
+    for (idx, entry) in planned.iter().enumerate() {
+        for other in planned[idx + 1..].iter().chain(&installed) {
+            check(entry, other, &mut conflicts);
+            check(other, entry, &mut conflicts);
+        }
+    }
+
+    conflicts
+}
+
+/// Records every rule of an entry matching another entry.
+fn check(entry: &Entry, other: &Entry, conflicts: &mut Vec<Conflict>) {
+    conflicts.extend(entry.rules(other).into_iter().map(|rule| Conflict {
+        other: other.member.clone(),
+        package: entry.member.clone(),
+        rule,
+    }));
+}
+
+/// Parses a list of package relations.
+fn parse(relations: &[String]) -> Vec<Dependency> {
+    relations.iter().map(|relation| Dependency::parse(relation)).collect()
+}
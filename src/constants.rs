@@ -1,2 +1,2 @@
-/// The URI to the API in use.
-pub const API_URI: &str = "https://aur.archlinux.org/rpc?v=5";
+/// The URI to the AUR instance in use by default.
+pub const AUR_URI: &str = "https://aur.archlinux.org";
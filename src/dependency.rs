@@ -0,0 +1,267 @@
+//! Typed representations of package dependency strings.
+//!
+//! Package relations such as [`InfoResult::dependencies`] are plain strings in
+//! the form used by pacman, e.g. `"python>=3.9"`. The types in this module
+//! parse them into a name and an optional version constraint, following the
+//! same rules as pacman.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aur::dependency::{Dependency, Operator, OptionalDependency};
+//!
+//! let dependency = Dependency::parse("python>=3.9");
+//! assert_eq!(dependency.name, "python");
+//!
+//! let constraint = dependency.version.unwrap();
+//! assert_eq!(constraint.operator, Operator::GreaterEqual);
+//! assert_eq!(constraint.version, "3.9");
+//!
+//! let optional = OptionalDependency::parse("foo: for bar support");
+//! assert_eq!(optional.dependency.name, "foo");
+//! assert_eq!(optional.description.unwrap(), "for bar support");
+//! ```
+//!
+//! [`InfoResult::dependencies`]: ../model/struct.InfoResult.html#structfield.dependencies
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use version::Version;
+
+/// A comparison operator in a versioned dependency.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Operator {
+    /// The `<` operator.
+    Less,
+    /// The `<=` operator.
+    LessEqual,
+    /// The `=` operator.
+    Equal,
+    /// The `>=` operator.
+    GreaterEqual,
+    /// The `>` operator.
+    Greater,
+}
+
+impl Operator {
+    /// Returns the textual form of the operator, such as `">="`.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Operator::Less => "<",
+            Operator::LessEqual => "<=",
+            Operator::Equal => "=",
+            Operator::GreaterEqual => ">=",
+            Operator::Greater => ">",
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A version constraint on a dependency, such as the `>=3.9` of
+/// `python>=3.9`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct VersionConstraint {
+    /// The comparison operator.
+    pub operator: Operator,
+    /// The version being compared against.
+    pub version: String,
+}
+
+impl VersionConstraint {
+    /// Whether the given version satisfies the constraint, comparing
+    /// versions as pacman does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use aur::dependency::Dependency;
+    /// use aur::version::Version;
+    ///
+    /// let constraint = Dependency::parse("python>=3.9").version.unwrap();
+    ///
+    /// assert!(constraint.is_satisfied_by(&Version::parse("3.11.5-1")));
+    /// assert!(!constraint.is_satisfied_by(&Version::parse("3.8.2-2")));
+    /// ```
+    pub fn is_satisfied_by(&self, version: &Version) -> bool {
+        let ordering = version.vercmp(&Version::parse(&self.version));
+
+        match self.operator {
+            Operator::Less => ordering == Ordering::Less,
+            Operator::LessEqual => ordering != Ordering::Greater,
+            Operator::Equal => ordering == Ordering::Equal,
+            Operator::GreaterEqual => ordering != Ordering::Less,
+            Operator::Greater => ordering == Ordering::Greater,
+        }
+    }
+}
+
+impl Display for VersionConstraint {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}{}", self.operator, self.version)
+    }
+}
+
+/// A dependency on a package, optionally constrained to certain versions.
+///
+/// This is used for all package relations: dependencies, conflicts, provides,
+/// and replaces.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Dependency {
+    /// The name of the depended upon package.
+    pub name: String,
+    /// The version constraint, if any.
+    pub version: Option<VersionConstraint>,
+}
+
+impl Dependency {
+    /// Parses a dependency string such as `"python>=3.9"`.
+    ///
+    /// Like pacman, parsing can not fail: a string without an operator is
+    /// entirely a package name.
+    pub fn parse(text: &str) -> Self {
+        // Operators are searched for in the same order as pacman, so that
+        // e.g. "foo<=1" is split at the '<' rather than at the '='.
+        let split = text.find('<')
+            .map(|idx| (idx, Operator::Less, Operator::LessEqual))
+            .or_else(|| text.find('>').map(|idx| {
+                (idx, Operator::Greater, Operator::GreaterEqual)
+            }))
+            .or_else(|| text.find('=').map(|idx| {
+                (idx, Operator::Equal, Operator::Equal)
+            }));
+
+        let (idx, strict, inclusive) = match split {
+            Some(split) => split,
+            None => return Dependency {
+                name: text.to_owned(),
+                version: None,
+            },
+        };
+
+        let rest = &text[idx + 1..];
+        let (operator, version) = if strict != inclusive && rest.starts_with('=') {
+            (inclusive, &rest[1..])
+        } else {
+            (strict, rest)
+        };
+
+        Dependency {
+            name: text[..idx].to_owned(),
+            version: Some(VersionConstraint {
+                operator,
+                version: version.to_owned(),
+            }),
+        }
+    }
+
+    /// Whether a package with the given name and version satisfies the
+    /// dependency.
+    pub fn is_satisfied_by(&self, name: &str, version: &Version) -> bool {
+        if self.name != name {
+            return false;
+        }
+
+        match self.version {
+            Some(ref constraint) => constraint.is_satisfied_by(version),
+            None => true,
+        }
+    }
+
+    /// Whether an entry of a package's `provides`, such as `"sh=5.2"`,
+    /// satisfies the dependency.
+    ///
+    /// Like pacman, an unversioned provision does not satisfy a dependency
+    /// with a version constraint.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use aur::dependency::Dependency;
+    ///
+    /// let dependency = Dependency::parse("java-runtime>=17");
+    ///
+    /// assert!(dependency.is_satisfied_by_provision(&Dependency::parse("java-runtime=21")));
+    /// assert!(!dependency.is_satisfied_by_provision(&Dependency::parse("java-runtime")));
+    /// ```
+    pub fn is_satisfied_by_provision(&self, provision: &Dependency) -> bool {
+        if self.name != provision.name {
+            return false;
+        }
+
+        let constraint = match self.version {
+            Some(ref constraint) => constraint,
+            None => return true,
+        };
+
+        match provision.version {
+            Some(ref provided) => constraint.is_satisfied_by(&Version::parse(&provided.version)),
+            None => false,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Dependency {
+    fn from(text: &'a str) -> Self {
+        Dependency::parse(text)
+    }
+}
+
+impl Display for Dependency {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(&self.name)?;
+
+        if let Some(ref version) = self.version {
+            Display::fmt(version, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An optional dependency, with a description of what it is used for.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OptionalDependency {
+    /// The optional dependency.
+    pub dependency: Dependency,
+    /// A description of what the optional dependency provides, if any.
+    pub description: Option<String>,
+}
+
+impl OptionalDependency {
+    /// Parses an optional dependency string such as
+    /// `"foo: for bar support"`.
+    pub fn parse(text: &str) -> Self {
+        let (dependency, description) = match text.find(": ") {
+            Some(idx) => (&text[..idx], Some(text[idx + 2..].to_owned())),
+            None => (text, None),
+        };
+
+        OptionalDependency {
+            dependency: Dependency::parse(dependency),
+            description,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for OptionalDependency {
+    fn from(text: &'a str) -> Self {
+        OptionalDependency::parse(text)
+    }
+}
+
+impl Display for OptionalDependency {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(&self.dependency, f)?;
+
+        if let Some(ref description) = self.description {
+            write!(f, ": {}", description)?;
+        }
+
+        Ok(())
+    }
+}
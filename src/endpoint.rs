@@ -0,0 +1,138 @@
+//! Configuration of the AUR instance in use.
+//!
+//! An [`Endpoint`] resolves the URLs of an AUR instance's resources, such as
+//! snapshot tarballs and git repositories, so that nothing depends upon a
+//! hard-coded host.
+//!
+//! [`Endpoint`]: struct.Endpoint.html
+
+use constants::AUR_URI;
+use std::fmt::Write;
+
+/// Characters left as they are in a path segment, besides ASCII letters and
+/// digits. `+` and `@` are common in package names.
+const PATH_SAFE: &str = "-._~+@";
+
+/// Characters left as they are in a query value, where `+` would be read as
+/// a space.
+const QUERY_SAFE: &str = "-._~@";
+
+/// The base URL of an AUR instance, used to resolve the URLs of its
+/// resources.
+///
+/// The default endpoint is the official AUR at `https://aur.archlinux.org`.
+///
+/// # Examples
+///
+/// ```rust
+/// use aur::Endpoint;
+///
+/// let endpoint = Endpoint::new("https://aur.example.com/");
+///
+/// assert_eq!(endpoint.clone_url("yay"), "https://aur.example.com/yay.git");
+/// assert_eq!(
+///     endpoint.log_url("yay"),
+///     "https://aur.example.com/cgit/aur.git/log/?h=yay",
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Endpoint {
+    base: String,
+}
+
+impl Endpoint {
+    /// Creates an endpoint from the base URL of an AUR instance, such as
+    /// `"https://aur.archlinux.org"`.
+    ///
+    /// A trailing slash is ignored.
+    pub fn new<S: Into<String>>(base: S) -> Self {
+        let mut base = base.into();
+
+        while base.ends_with('/') {
+            base.pop();
+        }
+
+        Endpoint {
+            base,
+        }
+    }
+
+    /// Returns the base URL of the instance, without a trailing slash.
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Returns the URL of the instance's RPC interface, including the version
+    /// of the interface in use.
+    pub fn rpc_url(&self) -> String {
+        format!("{}/rpc?v=5", self.base)
+    }
+
+    /// Resolves a server-relative path, such as an [`InfoResult::url_path`],
+    /// to an absolute URL.
+    ///
+    /// [`InfoResult::url_path`]: model/struct.InfoResult.html#structfield.url_path
+    pub fn resolve(&self, path: &str) -> String {
+        if path.starts_with('/') {
+            format!("{}{}", self.base, path)
+        } else {
+            format!("{}/{}", self.base, path)
+        }
+    }
+
+    /// Returns the URL of the snapshot tarball of a package base.
+    ///
+    /// The package base is percent-encoded, as are the names passed to the
+    /// other URL methods, so that it can not change the rest of the URL.
+    pub fn snapshot_url(&self, package_base: &str) -> String {
+        format!(
+            "{}/cgit/aur.git/snapshot/{}.tar.gz",
+            self.base,
+            encode(package_base, PATH_SAFE),
+        )
+    }
+
+    /// Returns the URL to clone the git repository of a package base from.
+    pub fn clone_url(&self, package_base: &str) -> String {
+        format!("{}/{}.git", self.base, encode(package_base, PATH_SAFE))
+    }
+
+    /// Returns the URL of a package's web page.
+    pub fn package_url(&self, name: &str) -> String {
+        format!("{}/packages/{}", self.base, encode(name, PATH_SAFE))
+    }
+
+    /// Returns the URL of the cgit log of a package base's git repository.
+    pub fn log_url(&self, package_base: &str) -> String {
+        format!(
+            "{}/cgit/aur.git/log/?h={}",
+            self.base,
+            encode(package_base, QUERY_SAFE),
+        )
+    }
+}
+
+/// Percent-encodes every byte of some text other than ASCII letters, digits,
+/// and the given safe characters.
+fn encode(text: &str, safe: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+
+    for byte in text.bytes() {
+        let c = byte as char;
+
+        if c.is_ascii_alphanumeric() || safe.contains(c) {
+            encoded.push(c);
+        } else {
+            // Writing to a `String` can not fail.
+            let _ = write!(encoded, "%{:02X}", byte);
+        }
+    }
+
+    encoded
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Endpoint::new(AUR_URI)
+    }
+}
@@ -1,6 +1,11 @@
+use model::ResponseType;
+use pacman::ParseError as DescError;
 use serde_json::Error as JsonError;
+use srcinfo::ParseError as SrcinfoError;
 use std::error::Error as StdError;
 use std::fmt::{Display, Error as FmtError, Formatter, Result as FmtResult};
+use std::io::Error as IoError;
+use std::path::PathBuf;
 use std::result::Result as StdResult;
 
 #[cfg(feature = "http")]
@@ -22,17 +27,32 @@ pub type Result<T> = StdResult<T, Error>;
 /// Enum encompassing the library's possible returned errors.
 #[derive(Debug)]
 pub enum Error {
+    /// An error indicating that package bases depend on each other in a
+    /// cycle, containing the path of the cycle, which starts and ends with
+    /// the same package base.
+    DependencyCycle(Vec<String>),
+    /// An error parsing a `desc` file of a pacman database, containing the
+    /// line it occurred on.
+    Desc(DescError),
     /// An error that occurred while formatting a string.
     Fmt(FmtError),
     /// An error from the `serde_json` crate while deserializing the body of an
     /// HTTP response.
     Json(JsonError),
+    /// An error returned by a git command, containing its error output.
+    Git(String),
     /// An error from the `http` crate.
     #[cfg(feature = "http")]
     Http(HttpError),
     /// An error from the `hyper` crate while performing an HTTP request.
     #[cfg(feature = "hyper")]
     Hyper(HyperError),
+    /// An error indicating that a package base name can not safely be used
+    /// as a file name, such as one containing a `/`, containing the name.
+    InvalidPackageBase(String),
+    /// An error from the filesystem, such as while writing or extracting a
+    /// snapshot tarball.
+    Io(IoError),
     /// An error from the `reqwest` crate while performing an HTTP request.
     #[cfg(feature = "reqwest")]
     Reqwest(ReqwestError),
@@ -45,9 +65,31 @@ pub enum Error {
     /// An error indicating a parsing issue when using `reqwest`.
     #[cfg(feature = "reqwest")]
     ReqwestParse(ReqwestUrlError),
+    /// An error returned by the RPC interface, such as for a query that
+    /// matches too many packages, containing the error message.
+    Rpc(String),
+    /// An error parsing a `.SRCINFO` file, containing the line it occurred
+    /// on.
+    Srcinfo(SrcinfoError),
+    /// An error indicating that a snapshot download responded with an
+    /// unsuccessful HTTP status code, containing the status code. Both bridges
+    /// return this for downloads, while RPC requests through the `reqwest`
+    /// bridge return `ReqwestBad` or `ReqwestInvalid`.
+    Status(u16),
     /// An error when building a request's URI from the `http` crate.
     #[cfg(feature = "http")]
     Uri(InvalidUri),
+    /// An error indicating that a response was of a type not answering the
+    /// request that was sent, containing the request type and the received
+    /// response type.
+    UnexpectedResponseType(ResponseType, ResponseType),
+    /// An error indicating that a tarball contained an entry that could write
+    /// or point outside of the destination directory, containing the path of
+    /// the entry.
+    UnsafeArchiveEntry(PathBuf),
+    /// An error indicating that an archive is compressed in an unsupported
+    /// format, containing the name of the format.
+    UnsupportedCompression(&'static str),
 }
 
 impl Display for Error {
@@ -59,11 +101,16 @@ impl Display for Error {
 impl StdError for Error {
     fn description(&self) -> &str {
         match *self {
+            Error::DependencyCycle(_) => "Dependency cycle",
+            Error::Desc(ref inner) => inner.description(),
             Error::Fmt(ref inner) => inner.description(),
+            Error::Git(ref message) => message,
             #[cfg(feature = "http")]
             Error::Http(ref inner) => inner.description(),
             #[cfg(feature = "hyper")]
             Error::Hyper(ref inner) => inner.description(),
+            Error::InvalidPackageBase(_) => "Invalid package base name",
+            Error::Io(ref inner) => inner.description(),
             Error::Json(ref inner) => inner.description(),
             #[cfg(feature = "reqwest")]
             Error::Reqwest(ref inner) => inner.description(),
@@ -73,8 +120,14 @@ impl StdError for Error {
             Error::ReqwestInvalid(_) => "Request invalid",
             #[cfg(feature = "reqwest")]
             Error::ReqwestParse(ref inner) => inner.description(),
+            Error::Rpc(ref message) => message,
+            Error::Srcinfo(ref inner) => inner.description(),
+            Error::Status(_) => "Unsuccessful status code",
             #[cfg(feature = "http")]
             Error::Uri(ref inner) => inner.description(),
+            Error::UnexpectedResponseType(..) => "Unexpected response type",
+            Error::UnsafeArchiveEntry(_) => "Unsafe archive entry",
+            Error::UnsupportedCompression(_) => "Unsupported compression",
         }
     }
 }
@@ -91,6 +144,24 @@ impl From<JsonError> for Error {
     }
 }
 
+impl From<IoError> for Error {
+    fn from(err: IoError) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<DescError> for Error {
+    fn from(err: DescError) -> Self {
+        Error::Desc(err)
+    }
+}
+
+impl From<SrcinfoError> for Error {
+    fn from(err: SrcinfoError) -> Self {
+        Error::Srcinfo(err)
+    }
+}
+
 #[cfg(feature = "http")]
 impl From<HttpError> for Error {
     fn from(err: HttpError) -> Self {
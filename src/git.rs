@@ -0,0 +1,201 @@
+//! Local clones of package base git repositories.
+//!
+//! Every package base on the AUR is a git repository. A [`Cache`] keeps clones
+//! of these repositories in a directory, cloning a package base the first
+//! time it is synced and fetching and fast-forwarding it afterwards.
+//!
+//! This shells out to the `git` executable, which must be on the `PATH`.
+//!
+//! # Examples
+//!
+//! Clone or update the `yay` package base, printing whether it changed:
+//!
+//! ```rust,no_run
+//! use aur::git::Cache;
+//!
+//! # fn try_main() -> aur::Result<()> {
+//! let cache = Cache::new("/var/cache/aur");
+//! let update = cache.sync("yay")?;
+//!
+//! if update.is_changed() {
+//!     println!("yay is now at {}", update.new_head);
+//! }
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`Cache`]: struct.Cache.html
+
+use endpoint::Endpoint;
+use snapshot;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use {Error, Result};
+
+/// A directory of clones of package base git repositories.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Cache {
+    directory: PathBuf,
+    endpoint: Endpoint,
+}
+
+impl Cache {
+    /// Creates a cache in a directory, cloning from the default [`Endpoint`].
+    ///
+    /// The directory is created when a package base is first cloned.
+    ///
+    /// [`Endpoint`]: ../struct.Endpoint.html
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        Cache::with_endpoint(directory, Endpoint::default())
+    }
+
+    /// Creates a cache in a directory, cloning from the given [`Endpoint`].
+    ///
+    /// Any URL understood by git may be used as the endpoint's base, such as
+    /// a `file://` URL of a directory of bare repositories.
+    ///
+    /// [`Endpoint`]: ../struct.Endpoint.html
+    pub fn with_endpoint<P: Into<PathBuf>>(directory: P, endpoint: Endpoint) -> Self {
+        Cache {
+            directory: directory.into(),
+            endpoint,
+        }
+    }
+
+    /// Returns the directory of the cache.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns the endpoint that package bases are cloned from.
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Returns the path of a package base's clone within the cache, whether
+    /// or not it has been cloned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPackageBase`] if the package base name can not
+    /// be used as a directory name, such as one containing a `/`.
+    ///
+    /// [`Error::InvalidPackageBase`]: ../enum.Error.html#variant.InvalidPackageBase
+    pub fn path(&self, package_base: &str) -> Result<PathBuf> {
+        snapshot::check_package_base(package_base)?;
+
+        Ok(self.directory.join(package_base))
+    }
+
+    /// Clones a package base into the cache, or fetches and fast-forwards it
+    /// if it has already been cloned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Git`] if a git command failed, such as for a package
+    /// base that does not exist or a clone that cannot be fast-forwarded.
+    ///
+    /// Returns [`Error::InvalidPackageBase`] if the package base name can not
+    /// be used as a directory name.
+    ///
+    /// Returns [`Error::Io`] if the cache directory could not be created or
+    /// git could not be run.
+    ///
+    /// [`Error::Git`]: ../enum.Error.html#variant.Git
+    /// [`Error::InvalidPackageBase`]: ../enum.Error.html#variant.InvalidPackageBase
+    /// [`Error::Io`]: ../enum.Error.html#variant.Io
+    pub fn sync(&self, package_base: &str) -> Result<Update> {
+        let path = self.path(package_base)?;
+
+        let old_head = if path.join(".git").is_dir() {
+            let old_head = head(&path)?;
+
+            git(&path, &["fetch", "--quiet", "origin"])?;
+            git(&path, &["merge", "--ff-only", "--quiet", "@{upstream}"])?;
+
+            Some(old_head)
+        } else {
+            fs::create_dir_all(&self.directory)?;
+
+            let url = self.endpoint.clone_url(package_base);
+            git(&self.directory, &[
+                OsStr::new("clone"),
+                OsStr::new("--quiet"),
+                OsStr::new("--"),
+                OsStr::new(&url),
+                path.as_os_str(),
+            ])?;
+
+            None
+        };
+
+        Ok(Update {
+            new_head: head(&path)?,
+            old_head,
+            path,
+        })
+    }
+}
+
+/// The result of syncing a package base into a [`Cache`].
+///
+/// [`Cache`]: struct.Cache.html
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Update {
+    /// The commit checked out after syncing.
+    pub new_head: String,
+    /// The commit checked out before syncing, or `None` if the package base
+    /// was newly cloned.
+    pub old_head: Option<String>,
+    /// The path of the clone.
+    pub path: PathBuf,
+}
+
+impl Update {
+    /// Whether the package base was newly cloned.
+    pub fn is_cloned(&self) -> bool {
+        self.old_head.is_none()
+    }
+
+    /// Whether the checked out commit changed, which is also the case for a
+    /// newly cloned package base.
+    pub fn is_changed(&self) -> bool {
+        match self.old_head {
+            Some(ref old_head) => *old_head != self.new_head,
+            None => true,
+        }
+    }
+}
+
+/// Returns the commit checked out in a repository.
+fn head(path: &Path) -> Result<String> {
+    git(path, &["rev-parse", "HEAD"])
+}
+
+/// Runs git in a directory, returning its trimmed standard output.
+pub(crate) fn git<S: AsRef<OsStr>>(directory: &Path, args: &[S]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+
+        Err(Error::Git(if stderr.is_empty() {
+            format!("git exited with {}", output.status)
+        } else {
+            stderr
+        }))
+    }
+}
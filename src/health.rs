@@ -0,0 +1,138 @@
+//! Heuristic assessment of how well maintained a package is.
+//!
+//! A [`PackageHealth`] report collects the signals commonly used to highlight
+//! risky AUR dependencies: whether the package is orphaned or flagged as
+//! out-of-date, how long it has gone without an update, how popular it is,
+//! and whether it is built from version control.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aur::health::{PackageHealth, Staleness};
+//! use aur::model::InfoResult;
+//!
+//! fn is_risky(package: &InfoResult) -> bool {
+//!     let health = PackageHealth::assess(package);
+//!
+//!     health.orphaned
+//!         || health.out_of_date_for.is_some()
+//!         || health.staleness == Staleness::Stale
+//! }
+//! ```
+//!
+//! [`PackageHealth`]: struct.PackageHealth.html
+
+use model::Package;
+use std::time::{Duration, SystemTime};
+use vcs::Vcs;
+
+const DAY: u64 = 60 * 60 * 24;
+
+/// A coarse bucket of a package's popularity or number of votes.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Tier {
+    /// No popularity or votes at all.
+    None,
+    /// A popularity below 1, or fewer than 10 votes.
+    Low,
+    /// A popularity below 10, or fewer than 100 votes.
+    Medium,
+    /// A popularity of 10 or more, or 100 or more votes.
+    High,
+}
+
+impl Tier {
+    /// Buckets a package's relative popularity.
+    pub fn from_popularity(popularity: f64) -> Self {
+        if popularity <= 0.0 {
+            Tier::None
+        } else if popularity < 1.0 {
+            Tier::Low
+        } else if popularity < 10.0 {
+            Tier::Medium
+        } else {
+            Tier::High
+        }
+    }
+
+    /// Buckets a package's number of votes.
+    pub fn from_votes(votes: u64) -> Self {
+        if votes == 0 {
+            Tier::None
+        } else if votes < 10 {
+            Tier::Low
+        } else if votes < 100 {
+            Tier::Medium
+        } else {
+            Tier::High
+        }
+    }
+}
+
+/// A coarse bucket of the time since a package was last updated.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Staleness {
+    /// Updated within the last 90 days.
+    Fresh,
+    /// Updated within the last year.
+    Aging,
+    /// Not updated for a year or more.
+    Stale,
+}
+
+impl Staleness {
+    /// Buckets the time elapsed since a package was last updated.
+    pub fn from_duration(since_update: Duration) -> Self {
+        let days = since_update.as_secs() / DAY;
+
+        if days < 90 {
+            Staleness::Fresh
+        } else if days < 365 {
+            Staleness::Aging
+        } else {
+            Staleness::Stale
+        }
+    }
+}
+
+/// A report of the health of a package.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PackageHealth {
+    /// Whether the package has no maintainer.
+    pub orphaned: bool,
+    /// How long the package has been flagged as out-of-date, if it is.
+    pub out_of_date_for: Option<Duration>,
+    /// The time elapsed since the package was last modified.
+    pub since_update: Duration,
+    /// The bucket of the time elapsed since the package was last modified.
+    pub staleness: Staleness,
+    /// The bucket of the package's relative popularity.
+    pub popularity: Tier,
+    /// The bucket of the package's number of votes.
+    pub votes: Tier,
+    /// Whether the package is built from a version control system, such as a
+    /// `-git` package.
+    pub vcs: bool,
+}
+
+impl PackageHealth {
+    /// Assesses the health of a package as of now.
+    pub fn assess<P: Package>(package: &P) -> Self {
+        PackageHealth::assess_at(package, SystemTime::now())
+    }
+
+    /// Assesses the health of a package as of the given point in time.
+    pub fn assess_at<P: Package>(package: &P, now: SystemTime) -> Self {
+        let since_update = package.time_since_update_at(now);
+
+        PackageHealth {
+            orphaned: package.is_orphaned(),
+            out_of_date_for: package.out_of_date_duration_at(now),
+            since_update,
+            staleness: Staleness::from_duration(since_update),
+            popularity: Tier::from_popularity(package.popularity()),
+            votes: Tier::from_votes(package.num_votes()),
+            vcs: Vcs::from_name(package.name()).is_some(),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Instrumentation of RPC operations.
+//!
+//! When the `tracing` feature is enabled, each operation performed by a bridge
+//! is recorded as a span carrying metadata about the request and its response.
+//! The span is entered while the request is in progress, so that events
+//! emitted during it are nested under the span. Without the feature, all of
+//! the instrumentation compiles to nothing.
+
+use serde_json::Error as JsonError;
+use Error;
+
+#[cfg(feature = "futures")]
+use futures::{Future, Poll};
+
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+#[cfg(feature = "tracing")]
+use tracing::field::Empty;
+#[cfg(feature = "tracing")]
+use tracing::Span;
+
+/// Instrumentation for a single RPC operation.
+///
+/// Bridges must create one of these for every request they send and report
+/// the progress of the request to it.
+#[derive(Clone, Debug)]
+pub struct Operation {
+    #[cfg(feature = "tracing")]
+    span: Span,
+    #[cfg(feature = "tracing")]
+    start: Instant,
+}
+
+impl Operation {
+    /// Starts instrumenting an operation of the given request type, such as
+    /// `"info"`, with the number of arguments and the final URL.
+    ///
+    /// The span's `retry_count` is always 0, as the bridges send every request
+    /// exactly once.
+    #[cfg(feature = "tracing")]
+    pub fn new(request_type: &'static str, arg_count: usize, url: &str) -> Self {
+        let span = info_span!(
+            "aur_rpc",
+            request_type = request_type,
+            arg_count = arg_count as u64,
+            url = url,
+            status = Empty,
+            response_size = Empty,
+            latency_ms = Empty,
+            result_count = Empty,
+            retry_count = 0u64,
+        );
+
+        Operation {
+            span,
+            start: Instant::now(),
+        }
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    #[inline]
+    pub fn new(_: &'static str, _: usize, _: &str) -> Self {
+        Operation {}
+    }
+
+    /// Runs a function with the operation's span entered.
+    #[cfg(feature = "reqwest")]
+    #[inline]
+    pub fn in_scope<F: FnOnce() -> T, T>(&self, f: F) -> T {
+        #[cfg(feature = "tracing")]
+        let _entered = self.span.enter();
+
+        f()
+    }
+
+    /// Wraps a future so that the operation's span is entered whenever it is
+    /// polled.
+    #[cfg(feature = "futures")]
+    #[inline]
+    pub fn instrument<F: Future>(&self, future: F) -> Instrumented<F> {
+        Instrumented {
+            inner: future,
+            #[cfg(feature = "tracing")]
+            span: self.span.clone(),
+        }
+    }
+
+    /// Records the HTTP status code of the response.
+    #[inline]
+    pub fn status(&self, _status: u16) {
+        #[cfg(feature = "tracing")]
+        self.span.record("status", _status);
+    }
+
+    /// Records the size of the response body in bytes.
+    #[inline]
+    pub fn response_size(&self, _size: usize) {
+        #[cfg(feature = "tracing")]
+        self.span.record("response_size", _size as u64);
+    }
+
+    /// Emits an event for a response body that failed to deserialize.
+    #[inline]
+    pub fn deserialize_error(&self, _why: &JsonError) {
+        #[cfg(feature = "tracing")]
+        self.span.in_scope(|| {
+            warn!(
+                error = %_why,
+                line = _why.line() as u64,
+                column = _why.column() as u64,
+                "failed to deserialize response",
+            );
+        });
+    }
+
+    /// Finishes the operation, recording its latency and either the number of
+    /// results or the error it resolved to.
+    #[inline]
+    pub fn finish(&self, _result: Result<u64, &Error>) {
+        #[cfg(feature = "tracing")]
+        {
+            let elapsed = self.start.elapsed();
+            let latency = elapsed.as_secs() * 1000
+                + u64::from(elapsed.subsec_millis());
+            self.span.record("latency_ms", latency);
+
+            match _result {
+                Ok(count) => {
+                    self.span.record("result_count", count);
+                },
+                Err(why) => self.span.in_scope(|| {
+                    warn!(error = %why, "request failed");
+                }),
+            }
+        }
+    }
+}
+
+/// A future with the span of an [`Operation`] entered whenever it is polled.
+///
+/// [`Operation`]: struct.Operation.html
+#[cfg(feature = "futures")]
+#[derive(Debug)]
+pub struct Instrumented<F> {
+    inner: F,
+    #[cfg(feature = "tracing")]
+    span: Span,
+}
+
+#[cfg(feature = "futures")]
+impl<F: Future> Future for Instrumented<F> {
+    type Item = F::Item;
+    type Error = F::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        #[cfg(feature = "tracing")]
+        let _entered = self.span.enter();
+
+        self.inner.poll()
+    }
+}
@@ -33,6 +33,23 @@
 //! version = "~0.1"
 //! ```
 //!
+//! Enabling the optional `tracing` feature instruments every request made by
+//! the bridges with a [`tracing`] span, recording the request type, argument
+//! count, URL, HTTP status, response size, latency, result count, and retry
+//! count. The bridges never retry a request, so the retry count is always 0.
+//! The span is entered while the request is in progress, and events are
+//! emitted under it when a response fails to deserialize.
+//!
+//! Enabling the optional `chrono` feature adds accessors returning the
+//! timestamps of packages as [`chrono`] `DateTime`s, in addition to the
+//! `SystemTime` accessors that are always available.
+//!
+//! Enabling the optional `regex` feature allows filtering results by a
+//! regular expression over their names with a [`Query`].
+//!
+//! Enabling the optional `zstd` feature allows reading zstd-compressed pacman
+//! sync databases, in addition to gzip-compressed and uncompressed ones.
+//!
 //! ### Examples
 //!
 //! Asynchronously request information for the `rust-nightly` package:
@@ -122,6 +139,9 @@
 //! ISC.
 //!
 //! [Arch User Repository]: https://aur.archlinux.org/
+//! [`Query`]: query/struct.Query.html
+//! [`chrono`]: https://docs.rs/chrono
+//! [`tracing`]: https://docs.rs/tracing
 //! [ci]: https://travis-ci.org/zeyla/aur.rs
 //! [ci-badge]: https://img.shields.io/travis/zeyla/aur.rs.svg?style=flat-square
 //! [docs]: https://docs.rs/crate/aur
@@ -129,30 +149,59 @@
 //! [LICENSE.md]: https://github.com/zeyla/aur.rs/blob/master/LICENSE.md
 //! [license]: https://opensource.org/licenses/ISC
 //! [license-badge]: https://img.shields.io/badge/license-ISC-blue.svg?style=flat-square
-//! [rust badge]: https://img.shields.io/badge/rust-1.21+-93450a.svg?style=flat-square
-//! [rust link]: https://blog.rust-lang.org/2017/10/12/Rust-1.21.html
+//! [rust badge]: https://img.shields.io/badge/rust-1.85+-93450a.svg?style=flat-square
+//! [rust link]: https://blog.rust-lang.org/2025/02/20/Rust-1.85.0.html
 #![deny(missing_docs)]
 
 #[macro_use] extern crate serde_derive;
 
+extern crate flate2;
 extern crate serde;
 extern crate serde_json;
+extern crate tar;
 
+#[cfg(feature = "chrono")]
+extern crate chrono;
 #[cfg(feature = "futures")]
 extern crate futures;
 #[cfg(feature = "http")]
 extern crate http;
 #[cfg(feature = "hyper")]
 extern crate hyper;
+#[cfg(feature = "regex")]
+extern crate regex;
 #[cfg(feature = "reqwest")]
 extern crate reqwest;
+#[cfg(feature = "tracing")]
+#[macro_use] extern crate tracing;
+#[cfg(feature = "zstd")]
+extern crate zstd;
 
+pub mod base;
 pub mod bridge;
+pub mod conflict;
+pub mod dependency;
+pub mod endpoint;
+pub mod git;
+pub mod health;
+pub mod license;
 pub mod model;
+pub mod pacman;
+pub mod pkgbuild;
+pub mod provider;
+pub mod query;
+pub mod resolve;
+pub mod snapshot;
+pub mod srcinfo;
+pub mod upgrade;
+pub mod vcs;
+pub mod version;
 
 mod constants;
 mod error;
+mod instrument;
 
+pub use endpoint::Endpoint;
 pub use error::{Error, Result};
 
 #[cfg(feature = "hyper")]
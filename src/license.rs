@@ -0,0 +1,431 @@
+//! Normalization and classification of package licenses.
+//!
+//! Package licenses are whatever their maintainers typed: SPDX expressions
+//! like `GPL-3.0-or-later`, Arch's historical common license names like
+//! `GPL3`, or `custom:` forms like `custom:MIT`. A [`License`] normalizes
+//! these to an SPDX expression where possible and classifies them as free,
+//! non-free, or unknown.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aur::license::{License, LicenseKind};
+//!
+//! let license = License::parse("GPL3");
+//! assert_eq!(license.spdx.as_ref().unwrap(), "GPL-3.0-only");
+//! assert_eq!(license.kind, LicenseKind::Free);
+//!
+//! let license = License::parse("custom:MIT");
+//! assert_eq!(license.spdx.as_ref().unwrap(), "MIT");
+//!
+//! let license = License::parse("custom:proprietary");
+//! assert_eq!(license.kind, LicenseKind::NonFree);
+//! ```
+//!
+//! [`License`]: struct.License.html
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The classification of a license.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LicenseKind {
+    /// A free software or open source license.
+    Free,
+    /// A license that is not free, such as a proprietary license or one
+    /// restricting commercial use.
+    NonFree,
+    /// A license that could not be classified.
+    Unknown,
+}
+
+impl LicenseKind {
+    /// Combines the kinds of licenses that all apply, as with an SPDX `AND`.
+    ///
+    /// The result is non-free if any license is non-free, free if all
+    /// licenses are free, and otherwise unknown.
+    pub fn and(self, other: LicenseKind) -> LicenseKind {
+        match (self, other) {
+            (LicenseKind::NonFree, _) | (_, LicenseKind::NonFree) => LicenseKind::NonFree,
+            (LicenseKind::Free, LicenseKind::Free) => LicenseKind::Free,
+            _ => LicenseKind::Unknown,
+        }
+    }
+
+    /// Combines the kinds of licenses that may be chosen between, as with an
+    /// SPDX `OR`.
+    ///
+    /// The result is free if any license is free, non-free if all licenses
+    /// are non-free, and otherwise unknown.
+    pub fn or(self, other: LicenseKind) -> LicenseKind {
+        match (self, other) {
+            (LicenseKind::Free, _) | (_, LicenseKind::Free) => LicenseKind::Free,
+            (LicenseKind::NonFree, LicenseKind::NonFree) => LicenseKind::NonFree,
+            _ => LicenseKind::Unknown,
+        }
+    }
+}
+
+/// A license of a package.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct License {
+    /// The license as it was given by the package.
+    pub original: String,
+    /// The license as an SPDX expression, if it could be normalized to one.
+    pub spdx: Option<String>,
+    /// The classification of the license.
+    pub kind: LicenseKind,
+}
+
+impl License {
+    /// Parses a license, normalizing it to an SPDX expression where possible.
+    ///
+    /// Parsing can not fail: licenses that are not recognized have no SPDX
+    /// expression and are of an unknown kind.
+    pub fn parse(text: &str) -> Self {
+        let trimmed = text.trim();
+
+        let (spdx, kind) = if is_expression(trimmed) {
+            match Parser::new(trimmed).parse() {
+                Some((spdx, kind)) => (spdx, kind),
+                None => (None, LicenseKind::Unknown),
+            }
+        } else {
+            identifier(trimmed)
+        };
+
+        License {
+            original: text.to_owned(),
+            spdx,
+            kind,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for License {
+    fn from(text: &'a str) -> Self {
+        License::parse(text)
+    }
+}
+
+impl Display for License {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(self.spdx.as_ref().unwrap_or(&self.original))
+    }
+}
+
+/// Classifies the licenses of a package as a whole.
+///
+/// Each license of a package applies to some of its files, so the licenses
+/// are combined as with an SPDX `AND`. A package without licenses is of an
+/// unknown kind.
+pub fn classify(licenses: &[License]) -> LicenseKind {
+    let mut iter = licenses.iter();
+
+    match iter.next() {
+        Some(first) => iter.fold(first.kind, |kind, license| kind.and(license.kind)),
+        None => LicenseKind::Unknown,
+    }
+}
+
+/// Arch's common license names and frequent aliases, lowercased, with their
+/// SPDX identifiers.
+const ALIASES: &[(&str, &str)] = &[
+    ("agpl", "AGPL-3.0-only"),
+    ("agpl3", "AGPL-3.0-only"),
+    ("agplv3", "AGPL-3.0-only"),
+    ("apache", "Apache-2.0"),
+    ("apache2", "Apache-2.0"),
+    ("apache-2", "Apache-2.0"),
+    ("apache 2.0", "Apache-2.0"),
+    ("artistic2.0", "Artistic-2.0"),
+    ("boost", "BSL-1.0"),
+    ("bsd", "BSD-3-Clause"),
+    ("bsd2", "BSD-2-Clause"),
+    ("bsd3", "BSD-3-Clause"),
+    ("cddl", "CDDL-1.0"),
+    ("cpl", "CPL-1.0"),
+    ("epl", "EPL-1.0"),
+    ("fdl", "GFDL-1.2-or-later"),
+    ("fdl1.2", "GFDL-1.2-only"),
+    ("fdl1.3", "GFDL-1.3-only"),
+    ("gpl", "GPL-2.0-or-later"),
+    ("gpl2", "GPL-2.0-only"),
+    ("gplv2", "GPL-2.0-only"),
+    ("gpl3", "GPL-3.0-only"),
+    ("gplv3", "GPL-3.0-only"),
+    ("lgpl", "LGPL-2.1-or-later"),
+    ("lgpl2", "LGPL-2.0-only"),
+    ("lgpl2.1", "LGPL-2.1-only"),
+    ("lgplv2.1", "LGPL-2.1-only"),
+    ("lgpl3", "LGPL-3.0-only"),
+    ("lgplv3", "LGPL-3.0-only"),
+    ("lppl", "LPPL-1.3c"),
+    ("mit", "MIT"),
+    ("mpl", "MPL-1.1"),
+    ("mpl2", "MPL-2.0"),
+    ("perlartistic", "Artistic-1.0-Perl"),
+    ("php", "PHP-3.01"),
+    ("psf", "PSF-2.0"),
+    ("python", "PSF-2.0"),
+    ("ruby", "Ruby"),
+    ("unlicense", "Unlicense"),
+    ("w3c", "W3C"),
+    ("zlib", "Zlib"),
+    ("zpl", "ZPL-2.1"),
+];
+
+/// SPDX identifiers of free software and open source licenses.
+const FREE: &[&str] = &[
+    "0BSD", "AFL-3.0", "AGPL-3.0-only", "AGPL-3.0-or-later", "Apache-2.0",
+    "Artistic-1.0-Perl", "Artistic-2.0", "BlueOak-1.0.0", "BSD-2-Clause",
+    "BSD-3-Clause", "BSL-1.0", "bzip2-1.0.6", "CC-BY-4.0", "CC-BY-SA-4.0",
+    "CC0-1.0", "CDDL-1.0", "CDDL-1.1", "CPL-1.0", "curl", "ECL-2.0",
+    "EPL-1.0", "EPL-2.0", "EUPL-1.2", "FTL", "GFDL-1.2-only",
+    "GFDL-1.2-or-later", "GFDL-1.3-only", "GFDL-1.3-or-later", "GPL-1.0-only",
+    "GPL-1.0-or-later", "GPL-2.0-only", "GPL-2.0-or-later", "GPL-3.0-only",
+    "GPL-3.0-or-later", "IJG", "ISC", "LGPL-2.0-only", "LGPL-2.0-or-later",
+    "LGPL-2.1-only", "LGPL-2.1-or-later", "LGPL-3.0-only",
+    "LGPL-3.0-or-later", "Libpng", "libpng-2.0", "LPPL-1.3c", "MIT", "MIT-0",
+    "MPL-1.1", "MPL-2.0", "MS-PL", "MS-RL", "NCSA", "OFL-1.1", "OpenSSL",
+    "OSL-3.0", "PHP-3.01", "PSF-2.0", "Python-2.0", "Ruby", "Unicode-3.0",
+    "Unicode-DFS-2016", "Unlicense", "UPL-1.0", "Vim", "W3C", "WTFPL", "X11",
+    "Zlib", "ZPL-2.1",
+];
+
+/// SPDX identifiers of licenses that are not free. Entries ending in `-` are
+/// prefixes of a family of licenses.
+const NON_FREE: &[&str] = &[
+    "BUSL-1.1", "CC-BY-NC-", "CC-BY-ND-", "Commons-Clause", "Elastic-2.0",
+    "SSPL-1.0",
+];
+
+/// Names commonly used by maintainers for proprietary licenses.
+const PROPRIETARY: &[&str] = &[
+    "commercial", "eula", "non-free", "nonfree", "proprietary", "shareware",
+];
+
+fn is_expression(text: &str) -> bool {
+    text.contains('(') || text.split_whitespace().any(|word| {
+        word == "AND" || word == "OR" || word == "WITH"
+    })
+}
+
+/// Normalizes and classifies a single license identifier.
+fn identifier(text: &str) -> (Option<String>, LicenseKind) {
+    let lower = text.to_lowercase();
+
+    if PROPRIETARY.contains(&&lower[..]) {
+        return (Some("LicenseRef-proprietary".to_owned()), LicenseKind::NonFree);
+    }
+
+    if lower == "unknown" || lower == "custom" {
+        return (None, LicenseKind::Unknown);
+    }
+
+    if let Some(name) = strip_prefix(text, "custom:") {
+        return match identifier(name.trim()) {
+            (None, _) => (None, LicenseKind::Unknown),
+            normalized => normalized,
+        };
+    }
+
+    if let Some(name) = strip_prefix(&lower, "licenseref-") {
+        let kind = if PROPRIETARY.iter().any(|prefix| name.starts_with(prefix)) {
+            LicenseKind::NonFree
+        } else {
+            LicenseKind::Unknown
+        };
+
+        return (Some(text.to_owned()), kind);
+    }
+
+    // Creative Commons licenses, given in Arch's old "CCPL:by-nc" form.
+    if lower.starts_with("ccpl") {
+        let kind = if lower.contains("nc") || lower.contains("nd") {
+            LicenseKind::NonFree
+        } else if lower.contains(':') {
+            LicenseKind::Free
+        } else {
+            LicenseKind::Unknown
+        };
+
+        return (None, kind);
+    }
+
+    if let Some(&(_, spdx)) = ALIASES.iter().find(|&&(alias, _)| alias == lower) {
+        return (Some(spdx.to_owned()), kind_of(spdx));
+    }
+
+    let known = FREE.iter()
+        .chain(NON_FREE.iter())
+        .find(|id| id.to_lowercase() == lower);
+
+    if let Some(id) = known {
+        return (Some((*id).to_owned()), kind_of(id));
+    }
+
+    let non_free = NON_FREE.iter().any(|prefix| {
+        prefix.ends_with('-') && lower.starts_with(&prefix.to_lowercase()[..])
+    });
+
+    if non_free {
+        return (Some(text.to_owned()), LicenseKind::NonFree);
+    }
+
+    (None, LicenseKind::Unknown)
+}
+
+/// Strips an ASCII prefix from the text, ignoring case.
+fn strip_prefix<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    match text.get(..prefix.len()) {
+        Some(start) if start.eq_ignore_ascii_case(prefix) => {
+            Some(&text[prefix.len()..])
+        },
+        _ => None,
+    }
+}
+
+fn kind_of(spdx: &str) -> LicenseKind {
+    if FREE.contains(&spdx) {
+        LicenseKind::Free
+    } else if NON_FREE.contains(&spdx) {
+        LicenseKind::NonFree
+    } else {
+        LicenseKind::Unknown
+    }
+}
+
+/// A recursive descent parser of SPDX expressions, normalizing each license
+/// identifier within the expression.
+///
+/// Operators bind from tightest to loosest as `WITH`, `AND`, then `OR`.
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+    normalized: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut tokens = Vec::new();
+        let mut start = None;
+
+        for (idx, ch) in text.char_indices() {
+            if ch == '(' || ch == ')' || ch.is_whitespace() {
+                if let Some(begin) = start.take() {
+                    tokens.push(&text[begin..idx]);
+                }
+
+                if !ch.is_whitespace() {
+                    tokens.push(&text[idx..idx + 1]);
+                }
+            } else if start.is_none() {
+                start = Some(idx);
+            }
+        }
+
+        if let Some(begin) = start {
+            tokens.push(&text[begin..]);
+        }
+
+        Parser {
+            tokens,
+            position: 0,
+            normalized: true,
+        }
+    }
+
+    /// Parses the whole expression, returning the normalized expression if
+    /// every identifier could be normalized, along with its kind.
+    fn parse(mut self) -> Option<(Option<String>, LicenseKind)> {
+        let (expression, kind) = self.or()?;
+
+        if self.position != self.tokens.len() {
+            return None;
+        }
+
+        let spdx = if self.normalized {
+            Some(expression)
+        } else {
+            None
+        };
+
+        Some((spdx, kind))
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).cloned()
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        self.position += 1;
+
+        token
+    }
+
+    fn or(&mut self) -> Option<(String, LicenseKind)> {
+        let (mut expression, mut kind) = self.and()?;
+
+        while self.peek() == Some("OR") {
+            self.position += 1;
+
+            let (right, right_kind) = self.and()?;
+            expression = format!("{} OR {}", expression, right);
+            kind = kind.or(right_kind);
+        }
+
+        Some((expression, kind))
+    }
+
+    fn and(&mut self) -> Option<(String, LicenseKind)> {
+        let (mut expression, mut kind) = self.atom()?;
+
+        while self.peek() == Some("AND") {
+            self.position += 1;
+
+            let (right, right_kind) = self.atom()?;
+            expression = format!("{} AND {}", expression, right);
+            kind = kind.and(right_kind);
+        }
+
+        Some((expression, kind))
+    }
+
+    fn atom(&mut self) -> Option<(String, LicenseKind)> {
+        let token = self.next()?;
+
+        if token == "(" {
+            let (expression, kind) = self.or()?;
+
+            if self.next() != Some(")") {
+                return None;
+            }
+
+            return Some((format!("({})", expression), kind));
+        }
+
+        if token == ")" || token == "AND" || token == "OR" || token == "WITH" {
+            return None;
+        }
+
+        let (spdx, kind) = identifier(token);
+        let mut expression = match spdx {
+            Some(spdx) => spdx,
+            None => {
+                self.normalized = false;
+
+                token.to_owned()
+            },
+        };
+
+        if self.peek() == Some("WITH") {
+            self.position += 1;
+
+            let exception = self.next()?;
+            expression = format!("{} WITH {}", expression, exception);
+        }
+
+        Some((expression, kind))
+    }
+}
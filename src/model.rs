@@ -1,8 +1,479 @@
 //! Models mapping the API.
+//!
+//! The owned [`SearchResult`] and [`InfoResult`] types allocate for every
+//! string. When deserializing large responses, the borrowed
+//! [`SearchResultRef`] and [`InfoResultRef`] variants can instead be used to
+//! borrow strings from the retained response body, converting to the owned
+//! types only when needed.
+//!
+//! [`InfoResult`]: struct.InfoResult.html
+//! [`InfoResultRef`]: struct.InfoResultRef.html
+//! [`SearchResult`]: struct.SearchResult.html
+//! [`SearchResultRef`]: struct.SearchResultRef.html
+
+use dependency::{Dependency, OptionalDependency};
+use endpoint::Endpoint;
+use license::License;
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use version::Version;
+use Error;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+/// Implements [`Package`] for a result type, all of which share the same
+/// field names.
+///
+/// [`Package`]: trait.Package.html
+macro_rules! impl_package {
+    ($($header:tt)*) => {
+        $($header)* {
+            fn description(&self) -> Option<&str> {
+                self.description.as_ref().map(|description| &**description)
+            }
+
+            fn first_submitted(&self) -> u64 {
+                self.first_submitted
+            }
+
+            fn id(&self) -> u64 {
+                self.id
+            }
+
+            fn last_modified(&self) -> u64 {
+                self.last_modified
+            }
+
+            fn maintainer(&self) -> Option<&str> {
+                self.maintainer.as_ref().map(|maintainer| &**maintainer)
+            }
+
+            fn name(&self) -> &str {
+                &self.name
+            }
+
+            fn num_votes(&self) -> u64 {
+                self.num_votes
+            }
+
+            fn out_of_date(&self) -> Option<u64> {
+                self.out_of_date
+            }
+
+            fn package_base(&self) -> &str {
+                &self.package_base
+            }
+
+            fn package_base_id(&self) -> u64 {
+                self.package_base_id
+            }
+
+            fn popularity(&self) -> f64 {
+                self.popularity
+            }
+
+            fn url(&self) -> Option<&str> {
+                self.url.as_ref().map(|url| &**url)
+            }
+
+            fn url_path(&self) -> &str {
+                &self.url_path
+            }
+
+            fn version(&self) -> &str {
+                &self.version
+            }
+        }
+    }
+}
+
+/// Implements the helpers of [`Package`] as inherent methods of a result
+/// type, so that they can be called without importing the trait.
+///
+/// [`Package`]: trait.Package.html
+macro_rules! impl_helpers {
+    ($($header:tt)*) => {
+        $($header)* {
+            /// Whether the package has no maintainer.
+            pub fn is_orphaned(&self) -> bool {
+                Package::is_orphaned(self)
+            }
+
+            /// Whether the package has been flagged as out-of-date.
+            pub fn is_out_of_date(&self) -> bool {
+                Package::is_out_of_date(self)
+            }
+
+            /// Parses the version of the package.
+            pub fn parsed_version(&self) -> Version {
+                Package::parsed_version(self)
+            }
+
+            /// Returns when the package was first submitted.
+            pub fn first_submitted_time(&self) -> SystemTime {
+                Package::first_submitted_time(self)
+            }
+
+            /// Returns when the package was last modified.
+            pub fn last_modified_time(&self) -> SystemTime {
+                Package::last_modified_time(self)
+            }
+
+            /// Returns when the package was flagged as out-of-date, if it is.
+            pub fn out_of_date_time(&self) -> Option<SystemTime> {
+                Package::out_of_date_time(self)
+            }
+
+            /// Returns how long the package has been flagged as out-of-date,
+            /// if it is.
+            pub fn out_of_date_duration(&self) -> Option<Duration> {
+                Package::out_of_date_duration(self)
+            }
+
+            /// Returns how long the package has been flagged as out-of-date
+            /// at the given point in time, if it is.
+            pub fn out_of_date_duration_at(&self, now: SystemTime)
+                -> Option<Duration> {
+                Package::out_of_date_duration_at(self, now)
+            }
+
+            /// Returns the time elapsed since the package was last modified.
+            pub fn time_since_update(&self) -> Duration {
+                Package::time_since_update(self)
+            }
+
+            /// Returns the time elapsed since the package was last modified
+            /// at the given point in time.
+            pub fn time_since_update_at(&self, now: SystemTime) -> Duration {
+                Package::time_since_update_at(self, now)
+            }
+
+            /// Returns when the package was first submitted.
+            #[cfg(feature = "chrono")]
+            pub fn first_submitted_datetime(&self) -> DateTime<Utc> {
+                Package::first_submitted_datetime(self)
+            }
+
+            /// Returns when the package was last modified.
+            #[cfg(feature = "chrono")]
+            pub fn last_modified_datetime(&self) -> DateTime<Utc> {
+                Package::last_modified_datetime(self)
+            }
+
+            /// Returns when the package was flagged as out-of-date, if it is.
+            #[cfg(feature = "chrono")]
+            pub fn out_of_date_datetime(&self) -> Option<DateTime<Utc>> {
+                Package::out_of_date_datetime(self)
+            }
+
+            /// Returns the absolute URL of the package base's snapshot
+            /// tarball on the given AUR instance.
+            pub fn snapshot_url(&self, endpoint: &Endpoint) -> String {
+                Package::snapshot_url(self, endpoint)
+            }
+
+            /// Returns the URL to clone the package base's git repository
+            /// from on the given AUR instance.
+            pub fn git_clone_url(&self, endpoint: &Endpoint) -> String {
+                Package::git_clone_url(self, endpoint)
+            }
+
+            /// Returns the URL of the package's web page on the given AUR
+            /// instance.
+            pub fn web_url(&self, endpoint: &Endpoint) -> String {
+                Package::web_url(self, endpoint)
+            }
+
+            /// Returns the URL of the cgit log of the package base's git
+            /// repository on the given AUR instance.
+            pub fn log_url(&self, endpoint: &Endpoint) -> String {
+                Package::log_url(self, endpoint)
+            }
+        }
+    }
+}
+
+/// The fields shared by every result type, allowing code to operate on any
+/// AUR package regardless of the type of request it was returned from.
+///
+/// Along with the shared fields, this provides typed accessors and helpers
+/// built upon them. The result types also have the helpers as inherent
+/// methods, so the trait only needs to be imported by generic code.
+///
+/// # Examples
+///
+/// Sort any kind of result by its number of votes:
+///
+/// ```rust
+/// use aur::model::Package;
+///
+/// fn sort_by_votes<P: Package>(packages: &mut [P]) {
+///     packages.sort_by(|a, b| b.num_votes().cmp(&a.num_votes()));
+/// }
+/// ```
+pub trait Package {
+    /// A longer description of the package.
+    fn description(&self) -> Option<&str>;
+
+    /// When the package was first submitted, as a Unix timestamp.
+    fn first_submitted(&self) -> u64;
+
+    /// The ID of the package.
+    fn id(&self) -> u64;
+
+    /// When the package was last modified, as a Unix timestamp.
+    fn last_modified(&self) -> u64;
+
+    /// The name of the package's maintainer.
+    fn maintainer(&self) -> Option<&str>;
+
+    /// The name of the package.
+    fn name(&self) -> &str;
+
+    /// The number of votes that the package has.
+    fn num_votes(&self) -> u64;
+
+    /// When the package was marked as out-of-date, as a Unix timestamp.
+    fn out_of_date(&self) -> Option<u64>;
+
+    /// The name of the base package.
+    fn package_base(&self) -> &str;
+
+    /// The ID of the base package.
+    fn package_base_id(&self) -> u64;
+
+    /// The relative popularity of the package.
+    fn popularity(&self) -> f64;
+
+    /// URL to the package's project home.
+    fn url(&self) -> Option<&str>;
+
+    /// Path to the package's snapshot tar.
+    fn url_path(&self) -> &str;
+
+    /// The version of the package.
+    fn version(&self) -> &str;
+
+    /// Whether the package has no maintainer.
+    fn is_orphaned(&self) -> bool {
+        self.maintainer().is_none()
+    }
+
+    /// Whether the package has been flagged as out-of-date.
+    fn is_out_of_date(&self) -> bool {
+        self.out_of_date().is_some()
+    }
+
+    /// Parses the version of the package.
+    fn parsed_version(&self) -> Version {
+        Version::parse(self.version())
+    }
+
+    /// Returns when the package was first submitted.
+    fn first_submitted_time(&self) -> SystemTime {
+        from_unix(self.first_submitted())
+    }
+
+    /// Returns when the package was last modified.
+    fn last_modified_time(&self) -> SystemTime {
+        from_unix(self.last_modified())
+    }
+
+    /// Returns when the package was flagged as out-of-date, if it is.
+    fn out_of_date_time(&self) -> Option<SystemTime> {
+        self.out_of_date().map(from_unix)
+    }
+
+    /// Returns how long the package has been flagged as out-of-date, if it
+    /// is.
+    fn out_of_date_duration(&self) -> Option<Duration> {
+        self.out_of_date_duration_at(SystemTime::now())
+    }
+
+    /// Returns how long the package has been flagged as out-of-date at the
+    /// given point in time, if it is.
+    fn out_of_date_duration_at(&self, now: SystemTime) -> Option<Duration> {
+        self.out_of_date_time().map(|time| elapsed(time, now))
+    }
+
+    /// Returns the time elapsed since the package was last modified.
+    fn time_since_update(&self) -> Duration {
+        self.time_since_update_at(SystemTime::now())
+    }
+
+    /// Returns the time elapsed since the package was last modified at the
+    /// given point in time.
+    fn time_since_update_at(&self, now: SystemTime) -> Duration {
+        elapsed(self.last_modified_time(), now)
+    }
+
+    /// Returns when the package was first submitted.
+    #[cfg(feature = "chrono")]
+    fn first_submitted_datetime(&self) -> DateTime<Utc> {
+        DateTime::from(self.first_submitted_time())
+    }
+
+    /// Returns when the package was last modified.
+    #[cfg(feature = "chrono")]
+    fn last_modified_datetime(&self) -> DateTime<Utc> {
+        DateTime::from(self.last_modified_time())
+    }
+
+    /// Returns when the package was flagged as out-of-date, if it is.
+    #[cfg(feature = "chrono")]
+    fn out_of_date_datetime(&self) -> Option<DateTime<Utc>> {
+        self.out_of_date_time().map(DateTime::from)
+    }
+
+    /// Returns the absolute URL of the package base's snapshot tarball on the
+    /// given AUR instance.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use aur::model::{InfoResult, Package};
+    /// use aur::Endpoint;
+    ///
+    /// # fn print(package: &InfoResult) {
+    /// let endpoint = Endpoint::default();
+    ///
+    /// println!("Download from {}", package.snapshot_url(&endpoint));
+    /// # }
+    /// ```
+    fn snapshot_url(&self, endpoint: &Endpoint) -> String {
+        endpoint.resolve(self.url_path())
+    }
+
+    /// Returns the URL to clone the package base's git repository from on the
+    /// given AUR instance.
+    fn git_clone_url(&self, endpoint: &Endpoint) -> String {
+        endpoint.clone_url(self.package_base())
+    }
+
+    /// Returns the URL of the package's web page on the given AUR instance.
+    fn web_url(&self, endpoint: &Endpoint) -> String {
+        endpoint.package_url(self.name())
+    }
+
+    /// Returns the URL of the cgit log of the package base's git repository
+    /// on the given AUR instance.
+    fn log_url(&self, endpoint: &Endpoint) -> String {
+        endpoint.log_url(self.package_base())
+    }
+}
+
+/// The type of a response, indicating the type of request that was
+/// performed.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum ResponseType {
+    /// The response to a failed request.
+    #[serde(rename = "error")]
+    Error,
+    /// The response to an info request, as returned by older versions of the
+    /// API.
+    #[serde(rename = "info")]
+    Info,
+    /// The response to an info request.
+    #[serde(rename = "multiinfo")]
+    Multiinfo,
+    /// The response to a search request.
+    #[serde(rename = "search")]
+    Search,
+    /// The response to a request for package name suggestions.
+    #[serde(rename = "suggest")]
+    Suggest,
+    /// The response to a request for package base name suggestions.
+    #[serde(rename = "suggest-pkgbase")]
+    SuggestPkgbase,
+}
+
+impl ResponseType {
+    /// Whether a response of this type answers a request of the given type.
+    ///
+    /// Info requests are answered by both [`Info`] and [`Multiinfo`]
+    /// responses, while other requests must be answered by a response of the
+    /// same type.
+    ///
+    /// [`Info`]: #variant.Info
+    /// [`Multiinfo`]: #variant.Multiinfo
+    pub fn answers(&self, request: ResponseType) -> bool {
+        match (*self, request) {
+            (ResponseType::Multiinfo, ResponseType::Info) => true,
+            (response, request) => response == request,
+        }
+    }
+}
+
+/// The field that a search request matches its query against.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SearchBy {
+    /// Match the names of packages.
+    Name,
+    /// Match the names and descriptions of packages, which is what a search
+    /// without a field matches.
+    NameDesc,
+    /// Match the maintainers of package bases.
+    Maintainer,
+    /// Match packages depending on the query.
+    Depends,
+    /// Match packages with a make dependency on the query.
+    MakeDepends,
+    /// Match packages with an optional dependency on the query.
+    OptDepends,
+    /// Match packages with a check dependency on the query.
+    CheckDepends,
+    /// Match packages providing the query.
+    Provides,
+    /// Match packages conflicting with the query.
+    Conflicts,
+    /// Match packages replacing the query.
+    Replaces,
+    /// Match the groups of packages.
+    Groups,
+    /// Match the keywords of package bases.
+    Keywords,
+    /// Match the co-maintainers of package bases.
+    CoMaintainers,
+    /// Match the submitters of package bases.
+    Submitter,
+}
+
+impl SearchBy {
+    /// Returns the value of the field as sent in the `by` parameter of a
+    /// request, such as `"provides"`.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            SearchBy::Name => "name",
+            SearchBy::NameDesc => "name-desc",
+            SearchBy::Maintainer => "maintainer",
+            SearchBy::Depends => "depends",
+            SearchBy::MakeDepends => "makedepends",
+            SearchBy::OptDepends => "optdepends",
+            SearchBy::CheckDepends => "checkdepends",
+            SearchBy::Provides => "provides",
+            SearchBy::Conflicts => "conflicts",
+            SearchBy::Replaces => "replaces",
+            SearchBy::Groups => "groups",
+            SearchBy::Keywords => "keywords",
+            SearchBy::CoMaintainers => "comaintainers",
+            SearchBy::Submitter => "submitter",
+        }
+    }
+}
 
 /// Result data for a search.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Search<T: Send + Sync> {
+    /// The error message of a failed request.
+    ///
+    /// This is only present when [`type_`] is [`ResponseType::Error`].
+    ///
+    /// [`ResponseType::Error`]: enum.ResponseType.html#variant.Error
+    /// [`type_`]: #structfield.type_
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
     /// A list of relevant results.
     pub results: Vec<T>,
     /// The number of results in the [`results`] field.
@@ -12,11 +483,66 @@ pub struct Search<T: Send + Sync> {
     pub result_count: u64,
     /// The type of search that was performed.
     #[serde(rename = "type")]
-    pub type_: String,
+    pub type_: ResponseType,
     /// The version of the API in use.
     pub version: u64,
 }
 
+impl<T: Send + Sync> Search<T> {
+    /// Checks that the response answers a request of the given type.
+    ///
+    /// The bridges perform this check on every response. It only needs to be
+    /// performed manually when deserializing responses directly, such as into
+    /// the borrowed result types.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// extern crate aur;
+    /// extern crate serde_json;
+    ///
+    /// use aur::model::{ResponseType, Search, SearchResult};
+    ///
+    /// # fn main() {
+    /// let body = r#"{
+    ///     "error": "Too many package results.",
+    ///     "resultcount": 0,
+    ///     "results": [],
+    ///     "type": "error",
+    ///     "version": 5
+    /// }"#;
+    ///
+    /// let search: Search<SearchResult> = serde_json::from_str(body).unwrap();
+    ///
+    /// assert!(search.verify(ResponseType::Search).is_err());
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Rpc`] with the error message if the response is an
+    /// error response.
+    ///
+    /// Returns [`Error::UnexpectedResponseType`] if the response is of a type
+    /// that doesn't answer the request.
+    ///
+    /// [`Error::Rpc`]: ../enum.Error.html#variant.Rpc
+    /// [`Error::UnexpectedResponseType`]: ../enum.Error.html#variant.UnexpectedResponseType
+    pub fn verify(self, request: ResponseType) -> ::Result<Self> {
+        if self.type_ == ResponseType::Error {
+            let message = self.error.unwrap_or_default();
+
+            return Err(Error::Rpc(message));
+        }
+
+        if !self.type_.answers(request) {
+            return Err(Error::UnexpectedResponseType(request, self.type_));
+        }
+
+        Ok(self)
+    }
+}
+
 /// A result for a search without additional information metadata.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SearchResult {
@@ -64,14 +590,44 @@ pub struct SearchResult {
     pub version: String,
 }
 
+impl_package!(impl Package for SearchResult);
+impl_helpers!(impl SearchResult);
+
+impl From<InfoResult> for SearchResult {
+    fn from(result: InfoResult) -> Self {
+        SearchResult {
+            description: result.description,
+            first_submitted: result.first_submitted,
+            id: result.id,
+            last_modified: result.last_modified,
+            maintainer: result.maintainer,
+            name: result.name,
+            num_votes: result.num_votes,
+            out_of_date: result.out_of_date,
+            package_base: result.package_base,
+            package_base_id: result.package_base_id,
+            popularity: result.popularity,
+            url: result.url,
+            url_path: result.url_path,
+            version: result.version,
+        }
+    }
+}
+
 /// A result for a search _with_ additional information metadata.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct InfoResult {
+    /// The packages that this package depends upon to run its test suite.
+    #[serde(default, rename = "CheckDepends")]
+    pub check_depends: Vec<String>,
+    /// The names of the package base's co-maintainers.
+    #[serde(default, rename = "CoMaintainers")]
+    pub co_maintainers: Vec<String>,
     /// A list of package names that conflicts with this package.
     #[serde(default, rename = "Conflicts")]
     pub conflicts: Vec<String>,
     /// The packages that this package depends upon.
-    #[serde(rename = "Depends")]
+    #[serde(default, rename = "Depends")]
     pub dependencies: Vec<String>,
     /// A longer description of the package.
     #[serde(rename = "Description")]
@@ -79,17 +635,20 @@ pub struct InfoResult {
     /// When the package was first submitted.
     #[serde(rename = "FirstSubmitted")]
     pub first_submitted: u64,
+    /// The groups that the package is a part of.
+    #[serde(default, rename = "Groups")]
+    pub groups: Vec<String>,
     /// The ID of the package.
     #[serde(rename = "ID")]
     pub id: u64,
     /// The keywords that the package has been marked with for queryability.
-    #[serde(rename = "Keywords")]
+    #[serde(default, rename = "Keywords")]
     pub keywords: Vec<String>,
     /// When the package was last modified.
     #[serde(rename = "LastModified")]
     pub last_modified: u64,
     /// The license(s) that the project is licensed under.
-    #[serde(rename = "License")]
+    #[serde(default, rename = "License")]
     pub license: Vec<String>,
     /// The name of the package's maintainer.
     #[serde(rename = "Maintainer")]
@@ -121,6 +680,14 @@ pub struct InfoResult {
     /// A list of packages this provides for.
     #[serde(default, rename = "Provides")]
     pub provides: Vec<String>,
+    /// A list of packages that this package replaces.
+    #[serde(default, rename = "Replaces")]
+    pub replaces: Vec<String>,
+    /// The name of the user that submitted the package base.
+    ///
+    /// This is `None` if the user no longer exists.
+    #[serde(rename = "Submitter")]
+    pub submitter: Option<String>,
     /// URL to the package's project home.
     #[serde(rename = "URL")]
     pub url: Option<String>,
@@ -131,3 +698,345 @@ pub struct InfoResult {
     #[serde(rename = "Version")]
     pub version: String,
 }
+
+impl InfoResult {
+    /// Parses the packages that this package depends upon to run its test
+    /// suite.
+    pub fn parsed_check_depends(&self) -> Vec<Dependency> {
+        parse_dependencies(&self.check_depends)
+    }
+
+    /// Parses the packages that this package conflicts with.
+    pub fn parsed_conflicts(&self) -> Vec<Dependency> {
+        parse_dependencies(&self.conflicts)
+    }
+
+    /// Parses the packages that this package depends upon.
+    ///
+    /// # Examples
+    ///
+    /// Check whether a package needs a minimum version of a dependency:
+    ///
+    /// ```rust
+    /// use aur::model::InfoResult;
+    ///
+    /// fn needs_minimum(package: &InfoResult, name: &str) -> bool {
+    ///     package.parsed_dependencies().iter().any(|dependency| {
+    ///         dependency.name == name && dependency.version.is_some()
+    ///     })
+    /// }
+    /// ```
+    pub fn parsed_dependencies(&self) -> Vec<Dependency> {
+        parse_dependencies(&self.dependencies)
+    }
+
+    /// Parses the licenses of the package, normalizing them to SPDX
+    /// expressions where possible.
+    pub fn parsed_licenses(&self) -> Vec<License> {
+        self.license.iter().map(|text| License::parse(text)).collect()
+    }
+
+    /// Parses the packages that this package depends upon to be made.
+    pub fn parsed_make_depends(&self) -> Vec<Dependency> {
+        parse_dependencies(&self.make_depends)
+    }
+
+    /// Parses the packages that this package optionally depends upon, along
+    /// with their descriptions.
+    pub fn parsed_optional_dependencies(&self) -> Vec<OptionalDependency> {
+        self.optional_dependencies
+            .iter()
+            .map(|text| OptionalDependency::parse(text))
+            .collect()
+    }
+
+    /// Parses the packages that this package provides for.
+    pub fn parsed_provides(&self) -> Vec<Dependency> {
+        parse_dependencies(&self.provides)
+    }
+
+    /// Parses the packages that this package replaces.
+    pub fn parsed_replaces(&self) -> Vec<Dependency> {
+        parse_dependencies(&self.replaces)
+    }
+}
+
+impl_package!(impl Package for InfoResult);
+impl_helpers!(impl InfoResult);
+
+/// A borrowed variant of [`SearchResult`].
+///
+/// Strings are borrowed from the deserialized input where possible, only
+/// allocating when the input contains escape sequences.
+///
+/// # Examples
+///
+/// Deserialize a search response while borrowing from the body:
+///
+/// ```rust
+/// extern crate aur;
+/// extern crate serde_json;
+///
+/// use aur::model::{Search, SearchResult, SearchResultRef};
+///
+/// # fn main() {
+/// let body = br#"{
+///     "version": 5,
+///     "type": "search",
+///     "resultcount": 1,
+///     "results": [{
+///         "ID": 1, "Name": "foo", "PackageBaseID": 1, "PackageBase": "foo",
+///         "Version": "1.0-1", "Description": null, "URL": null,
+///         "NumVotes": 0, "Popularity": 0, "OutOfDate": null,
+///         "Maintainer": null, "FirstSubmitted": 0, "LastModified": 0,
+///         "URLPath": "/cgit/aur.git/snapshot/foo.tar.gz"
+///     }]
+/// }"#;
+///
+/// let search: Search<SearchResultRef> = serde_json::from_slice(body).unwrap();
+/// assert_eq!(search.results[0].name, "foo");
+///
+/// let owned: SearchResult = search.results[0].clone().into();
+/// assert_eq!(owned.name, "foo");
+/// # }
+/// ```
+///
+/// [`SearchResult`]: struct.SearchResult.html
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SearchResultRef<'a> {
+    /// A longer description of the package.
+    #[serde(borrow, default, deserialize_with = "borrow_opt_str", rename = "Description")]
+    pub description: Option<Cow<'a, str>>,
+    /// When the package was first submitted.
+    #[serde(rename = "FirstSubmitted")]
+    pub first_submitted: u64,
+    /// The ID of the package.
+    #[serde(rename = "ID")]
+    pub id: u64,
+    /// When the package was last modified.
+    #[serde(rename = "LastModified")]
+    pub last_modified: u64,
+    /// The name of the package's maintainer.
+    #[serde(borrow, default, deserialize_with = "borrow_opt_str", rename = "Maintainer")]
+    pub maintainer: Option<Cow<'a, str>>,
+    /// The name of the package.
+    #[serde(borrow, rename = "Name")]
+    pub name: Cow<'a, str>,
+    /// The number of votes that the package has.
+    #[serde(rename = "NumVotes")]
+    pub num_votes: u64,
+    /// When the package was marked as out-of-date.
+    #[serde(rename = "OutOfDate")]
+    pub out_of_date: Option<u64>,
+    /// The name of the base package.
+    #[serde(borrow, rename = "PackageBase")]
+    pub package_base: Cow<'a, str>,
+    /// The ID of the base package.
+    #[serde(rename = "PackageBaseID")]
+    pub package_base_id: u64,
+    /// The relative popularity of the package.
+    #[serde(rename = "Popularity")]
+    pub popularity: f64,
+    /// URL to the package's project home.
+    #[serde(borrow, default, deserialize_with = "borrow_opt_str", rename = "URL")]
+    pub url: Option<Cow<'a, str>>,
+    /// Path to the package's snapshot tar.
+    #[serde(borrow, rename = "URLPath")]
+    pub url_path: Cow<'a, str>,
+    /// The version of the package.
+    #[serde(borrow, rename = "Version")]
+    pub version: Cow<'a, str>,
+}
+
+impl_package!(impl<'a> Package for SearchResultRef<'a>);
+impl_helpers!(impl<'a> SearchResultRef<'a>);
+
+impl<'a> From<SearchResultRef<'a>> for SearchResult {
+    fn from(result: SearchResultRef<'a>) -> Self {
+        SearchResult {
+            description: result.description.map(Cow::into_owned),
+            first_submitted: result.first_submitted,
+            id: result.id,
+            last_modified: result.last_modified,
+            maintainer: result.maintainer.map(Cow::into_owned),
+            name: result.name.into_owned(),
+            num_votes: result.num_votes,
+            out_of_date: result.out_of_date,
+            package_base: result.package_base.into_owned(),
+            package_base_id: result.package_base_id,
+            popularity: result.popularity,
+            url: result.url.map(Cow::into_owned),
+            url_path: result.url_path.into_owned(),
+            version: result.version.into_owned(),
+        }
+    }
+}
+
+/// A borrowed variant of [`InfoResult`].
+///
+/// Strings and lists of strings are borrowed from the deserialized input
+/// where possible, only allocating when the input contains escape sequences.
+///
+/// Refer to [`SearchResultRef`] for an example of deserializing borrowed
+/// results.
+///
+/// [`InfoResult`]: struct.InfoResult.html
+/// [`SearchResultRef`]: struct.SearchResultRef.html
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct InfoResultRef<'a> {
+    /// The packages that this package depends upon to run its test suite.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "CheckDepends")]
+    pub check_depends: Vec<Cow<'a, str>>,
+    /// The names of the package base's co-maintainers.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "CoMaintainers")]
+    pub co_maintainers: Vec<Cow<'a, str>>,
+    /// A list of package names that conflicts with this package.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "Conflicts")]
+    pub conflicts: Vec<Cow<'a, str>>,
+    /// The packages that this package depends upon.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "Depends")]
+    pub dependencies: Vec<Cow<'a, str>>,
+    /// A longer description of the package.
+    #[serde(borrow, default, deserialize_with = "borrow_opt_str", rename = "Description")]
+    pub description: Option<Cow<'a, str>>,
+    /// When the package was first submitted.
+    #[serde(rename = "FirstSubmitted")]
+    pub first_submitted: u64,
+    /// The groups that the package is a part of.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "Groups")]
+    pub groups: Vec<Cow<'a, str>>,
+    /// The ID of the package.
+    #[serde(rename = "ID")]
+    pub id: u64,
+    /// The keywords that the package has been marked with for queryability.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "Keywords")]
+    pub keywords: Vec<Cow<'a, str>>,
+    /// When the package was last modified.
+    #[serde(rename = "LastModified")]
+    pub last_modified: u64,
+    /// The license(s) that the project is licensed under.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "License")]
+    pub license: Vec<Cow<'a, str>>,
+    /// The name of the package's maintainer.
+    #[serde(borrow, default, deserialize_with = "borrow_opt_str", rename = "Maintainer")]
+    pub maintainer: Option<Cow<'a, str>>,
+    /// The list of dependencies to make the package.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "MakeDepends")]
+    pub make_depends: Vec<Cow<'a, str>>,
+    /// The name of the package.
+    #[serde(borrow, rename = "Name")]
+    pub name: Cow<'a, str>,
+    /// The number of votes that the package has.
+    #[serde(rename = "NumVotes")]
+    pub num_votes: u64,
+    /// The packages that this package optionally depends upon.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "OptDepends")]
+    pub optional_dependencies: Vec<Cow<'a, str>>,
+    /// When the package was marked as out-of-date.
+    #[serde(rename = "OutOfDate")]
+    pub out_of_date: Option<u64>,
+    /// The name of the base package.
+    #[serde(borrow, rename = "PackageBase")]
+    pub package_base: Cow<'a, str>,
+    /// The ID of the base package.
+    #[serde(rename = "PackageBaseID")]
+    pub package_base_id: u64,
+    /// The relative popularity of the package.
+    #[serde(rename = "Popularity")]
+    pub popularity: f64,
+    /// A list of packages this provides for.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "Provides")]
+    pub provides: Vec<Cow<'a, str>>,
+    /// A list of packages that this package replaces.
+    #[serde(borrow, default, deserialize_with = "borrow_strs", rename = "Replaces")]
+    pub replaces: Vec<Cow<'a, str>>,
+    /// The name of the user that submitted the package base.
+    ///
+    /// This is `None` if the user no longer exists.
+    #[serde(borrow, default, deserialize_with = "borrow_opt_str", rename = "Submitter")]
+    pub submitter: Option<Cow<'a, str>>,
+    /// URL to the package's project home.
+    #[serde(borrow, default, deserialize_with = "borrow_opt_str", rename = "URL")]
+    pub url: Option<Cow<'a, str>>,
+    /// Path to the package's snapshot tar.
+    #[serde(borrow, rename = "URLPath")]
+    pub url_path: Cow<'a, str>,
+    /// The version of the package.
+    #[serde(borrow, rename = "Version")]
+    pub version: Cow<'a, str>,
+}
+
+impl_package!(impl<'a> Package for InfoResultRef<'a>);
+impl_helpers!(impl<'a> InfoResultRef<'a>);
+
+impl<'a> From<InfoResultRef<'a>> for InfoResult {
+    fn from(result: InfoResultRef<'a>) -> Self {
+        InfoResult {
+            check_depends: into_owned_strs(result.check_depends),
+            co_maintainers: into_owned_strs(result.co_maintainers),
+            conflicts: into_owned_strs(result.conflicts),
+            dependencies: into_owned_strs(result.dependencies),
+            description: result.description.map(Cow::into_owned),
+            first_submitted: result.first_submitted,
+            groups: into_owned_strs(result.groups),
+            id: result.id,
+            keywords: into_owned_strs(result.keywords),
+            last_modified: result.last_modified,
+            license: into_owned_strs(result.license),
+            maintainer: result.maintainer.map(Cow::into_owned),
+            make_depends: into_owned_strs(result.make_depends),
+            name: result.name.into_owned(),
+            num_votes: result.num_votes,
+            optional_dependencies: into_owned_strs(result.optional_dependencies),
+            out_of_date: result.out_of_date,
+            package_base: result.package_base.into_owned(),
+            package_base_id: result.package_base_id,
+            popularity: result.popularity,
+            provides: into_owned_strs(result.provides),
+            replaces: into_owned_strs(result.replaces),
+            submitter: result.submitter.map(Cow::into_owned),
+            url: result.url.map(Cow::into_owned),
+            url_path: result.url_path.into_owned(),
+            version: result.version.into_owned(),
+        }
+    }
+}
+
+/// Wrapper to borrow a string when it is nested within another type, as
+/// serde only borrows `Cow`s that are direct fields.
+#[derive(Deserialize)]
+struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+fn borrow_opt_str<'de: 'a, 'a, D>(deserializer: D)
+    -> Result<Option<Cow<'a, str>>, D::Error> where D: Deserializer<'de> {
+    let value: Option<Borrowed<'a>> = Deserialize::deserialize(deserializer)?;
+
+    Ok(value.map(|borrowed| borrowed.0))
+}
+
+fn borrow_strs<'de: 'a, 'a, D>(deserializer: D)
+    -> Result<Vec<Cow<'a, str>>, D::Error> where D: Deserializer<'de> {
+    let values: Vec<Borrowed<'a>> = Deserialize::deserialize(deserializer)?;
+
+    Ok(values.into_iter().map(|borrowed| borrowed.0).collect())
+}
+
+/// Converts a Unix timestamp in seconds, as used on the wire, to a
+/// `SystemTime`.
+fn from_unix(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+/// Returns the time elapsed from `time` to `now`, which is zero if `now` is
+/// earlier due to clock skew.
+fn elapsed(time: SystemTime, now: SystemTime) -> Duration {
+    now.duration_since(time).unwrap_or_else(|_| Duration::from_secs(0))
+}
+
+fn parse_dependencies(values: &[String]) -> Vec<Dependency> {
+    values.iter().map(|text| Dependency::parse(text)).collect()
+}
+
+fn into_owned_strs(values: Vec<Cow<str>>) -> Vec<String> {
+    values.into_iter().map(Cow::into_owned).collect()
+}
@@ -0,0 +1,116 @@
+//! The local database of installed packages.
+//!
+//! The local database is a directory holding a `<name>-<version>` directory
+//! for every installed package, each containing the package's `desc` file.
+//!
+//! # Examples
+//!
+//! List the installed packages that are not in any sync database, like
+//! `pacman -Qm`:
+//!
+//! ```rust,no_run
+//! use aur::pacman::local::LocalDb;
+//!
+//! # fn try_main() -> aur::Result<()> {
+//! let packages = LocalDb::default().packages()?;
+//! let repo = ["glibc", "pacman"];
+//!
+//! for package in LocalDb::foreign(&packages, &repo) {
+//!     println!("{} {}", package.name, package.version);
+//! }
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+
+use super::{Package, DEFAULT_DB_PATH};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use Result;
+
+/// The local database of installed packages.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct LocalDb {
+    directory: PathBuf,
+}
+
+impl LocalDb {
+    /// Opens the local database in pacman's database directory, such as
+    /// `/var/lib/pacman`.
+    pub fn new<P: AsRef<Path>>(db_path: P) -> Self {
+        LocalDb::with_directory(db_path.as_ref().join("local"))
+    }
+
+    /// Opens a local database directory, such as `/var/lib/pacman/local`.
+    pub fn with_directory<P: Into<PathBuf>>(directory: P) -> Self {
+        LocalDb {
+            directory: directory.into(),
+        }
+    }
+
+    /// Returns the directory of the database.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Reads every installed package, sorted by name.
+    ///
+    /// Entries of the directory that are not directories, such as
+    /// `ALPM_DB_VERSION`, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Desc`] if a `desc` file is malformed, with the path
+    /// of the file.
+    ///
+    /// Returns [`Error::Io`] if the directory or a `desc` file could not be
+    /// read.
+    ///
+    /// [`Error::Desc`]: ../../enum.Error.html#variant.Desc
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    pub fn packages(&self) -> Result<Vec<Package>> {
+        let mut packages = Vec::new();
+
+        for entry in fs::read_dir(&self.directory)? {
+            let entry = entry?;
+
+            if entry.file_type()?.is_dir() {
+                packages.push(Package::parse_file(entry.path().join("desc"))?);
+            }
+        }
+
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(packages)
+    }
+
+    /// Returns the packages whose names are not among the names of the
+    /// packages in the sync databases, in the order given.
+    ///
+    /// These are the packages installed from outside of the repositories,
+    /// such as from the AUR.
+    pub fn foreign<I, S>(packages: &[Package], sync_names: I) -> Vec<&Package>
+        where I: IntoIterator<Item = S>,
+              S: AsRef<str> {
+        let sync_names = sync_names
+            .into_iter()
+            .map(|name| name.as_ref().to_owned())
+            .collect::<HashSet<_>>();
+
+        packages
+            .iter()
+            .filter(|package| !sync_names.contains(&package.name))
+            .collect()
+    }
+}
+
+impl Default for LocalDb {
+    /// Opens the local database in pacman's default database directory.
+    fn default() -> Self {
+        LocalDb::new(DEFAULT_DB_PATH)
+    }
+}
@@ -0,0 +1,309 @@
+//! Reading pacman's package databases without libalpm.
+//!
+//! Both the local database of installed packages and the sync databases of
+//! the repositories describe each package with a `desc` file of `%FIELD%`
+//! headers, each followed by one value per line and a blank line. This
+//! module parses these files into a [`Package`], the [`local`] module reads
+//! the local database, and the [`sync`] module reads sync databases.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aur::pacman::{InstallReason, Package};
+//!
+//! let desc = "%NAME%\nyay\n\n%VERSION%\n12.3.5-1\n\n%REASON%\n1\n\n%DEPENDS%\npacman>5\ngit\n";
+//! let package = Package::parse(desc).unwrap();
+//!
+//! assert_eq!(package.name, "yay");
+//! assert_eq!(package.reason, InstallReason::Dependency);
+//! assert_eq!(package.depends, vec!["pacman>5", "git"]);
+//! ```
+//!
+//! [`Package`]: struct.Package.html
+//! [`local`]: local/index.html
+//! [`sync`]: sync/index.html
+
+pub mod local;
+pub mod sync;
+
+use conflict::InstalledPackage;
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+use version::Version;
+use {Error, Result};
+
+/// The default location of pacman's databases.
+pub const DEFAULT_DB_PATH: &str = "/var/lib/pacman";
+
+/// Why a package was installed.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum InstallReason {
+    /// The package was installed explicitly, which is also the reason for
+    /// packages without a recorded reason.
+    #[default]
+    Explicit,
+    /// The package was installed as a dependency of another package.
+    Dependency,
+}
+
+/// A package described by a `desc` file of a pacman database.
+///
+/// Fields that are only recorded by one kind of database, such as the
+/// install date of an installed package, are empty for the other.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Package {
+    /// The architecture the package was built for.
+    pub arch: Option<String>,
+    /// The name of the package base the package was built from.
+    pub base: Option<String>,
+    /// When the package was built, as a UNIX timestamp.
+    pub build_date: Option<u64>,
+    /// The packages that the package depends upon to run its test suite.
+    pub check_depends: Vec<String>,
+    /// The packages that the package conflicts with.
+    pub conflicts: Vec<String>,
+    /// The packages that the package depends upon.
+    pub depends: Vec<String>,
+    /// A description of the package.
+    pub description: Option<String>,
+    /// The groups that the package belongs to.
+    pub groups: Vec<String>,
+    /// When the package was installed, as a UNIX timestamp.
+    pub install_date: Option<u64>,
+    /// The size of the installed package in bytes.
+    pub installed_size: Option<u64>,
+    /// The licenses of the package.
+    pub license: Vec<String>,
+    /// The packages that the package depends upon to be built.
+    pub make_depends: Vec<String>,
+    /// The name of the package.
+    pub name: String,
+    /// The optional dependencies of the package.
+    pub opt_depends: Vec<String>,
+    /// The person who built the package.
+    pub packager: Option<String>,
+    /// The packages that the package provides.
+    pub provides: Vec<String>,
+    /// Why the package was installed, which is always
+    /// [`InstallReason::Explicit`] for a package of a sync database.
+    ///
+    /// [`InstallReason::Explicit`]: enum.InstallReason.html#variant.Explicit
+    pub reason: InstallReason,
+    /// The packages that the package replaces.
+    pub replaces: Vec<String>,
+    /// The URL of the package's upstream project.
+    pub url: Option<String>,
+    /// The version of the package.
+    pub version: String,
+}
+
+impl Package {
+    /// Parses the text of a `desc` file.
+    ///
+    /// Unknown fields are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Desc`] if the text is malformed or lacks the name or
+    /// version.
+    ///
+    /// [`Error::Desc`]: ../enum.Error.html#variant.Desc
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut package = Package::default();
+        let mut seen = Vec::new();
+        let mut field: Option<&str> = None;
+        let mut last_line = 0;
+
+        for (idx, line) in text.lines().enumerate() {
+            let error = |kind| Error::Desc(ParseError {
+                kind,
+                line: idx + 1,
+                path: None,
+            });
+            last_line = idx + 1;
+
+            if line.is_empty() {
+                field = None;
+
+                continue;
+            }
+
+            let name = match field {
+                Some(name) => name,
+                None => {
+                    if !line.starts_with('%') || !line.ends_with('%') || line.len() < 2 {
+                        return Err(error(ParseErrorKind::MissingField));
+                    }
+
+                    let name = &line[1..line.len() - 1];
+
+                    if seen.contains(&name) {
+                        return Err(error(ParseErrorKind::DuplicateField(name.to_owned())));
+                    }
+
+                    seen.push(name);
+                    field = Some(name);
+
+                    continue;
+                },
+            };
+
+            let number = || {
+                line.parse::<u64>()
+                    .map(Some)
+                    .map_err(|_| error(ParseErrorKind::InvalidNumber(name.to_owned())))
+            };
+
+            match name {
+                "ARCH" => package.arch = Some(line.to_owned()),
+                "BASE" => package.base = Some(line.to_owned()),
+                "BUILDDATE" => package.build_date = number()?,
+                "CHECKDEPENDS" => package.check_depends.push(line.to_owned()),
+                "CONFLICTS" => package.conflicts.push(line.to_owned()),
+                "DEPENDS" => package.depends.push(line.to_owned()),
+                "DESC" => package.description = Some(line.to_owned()),
+                "GROUPS" => package.groups.push(line.to_owned()),
+                "INSTALLDATE" => package.install_date = number()?,
+                "ISIZE" | "SIZE" => package.installed_size = number()?,
+                "LICENSE" => package.license.push(line.to_owned()),
+                "MAKEDEPENDS" => package.make_depends.push(line.to_owned()),
+                "NAME" => package.name = line.to_owned(),
+                "OPTDEPENDS" => package.opt_depends.push(line.to_owned()),
+                "PACKAGER" => package.packager = Some(line.to_owned()),
+                "PROVIDES" => package.provides.push(line.to_owned()),
+                "REASON" => {
+                    package.reason = match line {
+                        "0" => InstallReason::Explicit,
+                        "1" => InstallReason::Dependency,
+                        _ => return Err(error(ParseErrorKind::InvalidNumber(name.to_owned()))),
+                    };
+                },
+                "REPLACES" => package.replaces.push(line.to_owned()),
+                "URL" => package.url = Some(line.to_owned()),
+                "VERSION" => package.version = line.to_owned(),
+                _ => {},
+            }
+        }
+
+        for &(key, value) in &[("NAME", &package.name), ("VERSION", &package.version)] {
+            if value.is_empty() {
+                return Err(Error::Desc(ParseError {
+                    kind: ParseErrorKind::MissingValue(key),
+                    line: last_line,
+                    path: None,
+                }));
+            }
+        }
+
+        Ok(package)
+    }
+
+    /// Reads and parses a `desc` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Desc`] if the file is malformed or lacks the name or
+    /// version, with the path of the file.
+    ///
+    /// Returns [`Error::Io`] if the file could not be read.
+    ///
+    /// [`Error::Desc`]: ../enum.Error.html#variant.Desc
+    /// [`Error::Io`]: ../enum.Error.html#variant.Io
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        Package::parse_at(&fs::read_to_string(path)?, path)
+    }
+
+    /// Parses the text of a `desc` file, attributing errors to its path.
+    fn parse_at(text: &str, path: &Path) -> Result<Self> {
+        Package::parse(text).map_err(|why| match why {
+            Error::Desc(mut inner) => {
+                inner.path = Some(path.to_owned());
+
+                Error::Desc(inner)
+            },
+            other => other,
+        })
+    }
+
+    /// Parses the version of the package.
+    pub fn parsed_version(&self) -> Version {
+        Version::parse(&self.version)
+    }
+}
+
+impl<'a> From<&'a Package> for InstalledPackage {
+    fn from(package: &'a Package) -> Self {
+        InstalledPackage {
+            conflicts: package.conflicts.clone(),
+            name: package.name.clone(),
+            provides: package.provides.clone(),
+            replaces: package.replaces.clone(),
+            version: package.version.clone(),
+        }
+    }
+}
+
+/// An error parsing a `desc` file, with the number of the line it occurred
+/// on.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ParseError {
+    /// The kind of error.
+    pub kind: ParseErrorKind,
+    /// The number of the line the error occurred on, starting at 1.
+    pub line: usize,
+    /// The path of the file, if it was read from one.
+    pub path: Option<PathBuf>,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if let Some(ref path) = self.path {
+            write!(f, "{}: ", path.display())?;
+        }
+
+        write!(f, "line {}: ", self.line)?;
+
+        match self.kind {
+            ParseErrorKind::DuplicateField(ref field) => {
+                write!(f, "duplicate field %{}%", field)
+            },
+            ParseErrorKind::InvalidNumber(ref field) => {
+                write!(f, "invalid value for %{}%", field)
+            },
+            ParseErrorKind::MissingField => f.write_str("value outside of a field"),
+            ParseErrorKind::MissingValue(field) => write!(f, "missing %{}%", field),
+        }
+    }
+}
+
+impl StdError for ParseError {
+    fn description(&self) -> &str {
+        match self.kind {
+            ParseErrorKind::DuplicateField(_) => "Duplicate field",
+            ParseErrorKind::InvalidNumber(_) => "Invalid number",
+            ParseErrorKind::MissingField => "Value outside of a field",
+            ParseErrorKind::MissingValue(_) => "Missing value",
+        }
+    }
+}
+
+/// The kind of a [`ParseError`].
+///
+/// [`ParseError`]: struct.ParseError.html
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ParseErrorKind {
+    /// A field was given more than once.
+    DuplicateField(String),
+    /// A field holding a number or an install reason was given an invalid
+    /// one.
+    InvalidNumber(String),
+    /// A line is neither blank, a `%FIELD%` header, nor a value following
+    /// one.
+    MissingField,
+    /// A required field was not given, such as `NAME`.
+    MissingValue(&'static str),
+}
@@ -0,0 +1,269 @@
+//! Sync databases of the official repositories.
+//!
+//! A sync database, such as `/var/lib/pacman/sync/core.db`, is a tarball of
+//! a `<name>-<version>/desc` file for every package in a repository. The
+//! tarball is usually gzip-compressed, and zstd-compressed databases can be
+//! read with the `zstd` feature enabled.
+//!
+//! Cross-referencing AUR packages with the sync databases finds the packages
+//! that have moved to the official repositories, or that are provided by a
+//! package there.
+//!
+//! # Examples
+//!
+//! Print the AUR packages that are now available from the repositories:
+//!
+//! ```rust,no_run
+//! use aur::model::InfoResult;
+//! use aur::pacman::sync::{self, SyncDb};
+//!
+//! fn print_superseded(packages: &[InfoResult]) -> aur::Result<()> {
+//!     let dbs = SyncDb::open_all("/var/lib/pacman")?;
+//!
+//!     for superseded in sync::superseded(packages, &dbs) {
+//!         println!("{} is in {} as {}", superseded.aur.name, superseded.db, superseded.package.name);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use dependency::Dependency;
+use flate2::read::GzDecoder;
+use model::InfoResult;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+use super::Package;
+use tar::Archive;
+use {Error, Result};
+
+/// The magic bytes starting a gzip stream.
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+/// The magic bytes starting a zstd frame.
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+/// The magic bytes starting an xz stream.
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+/// The magic bytes starting a bzip2 stream.
+const BZIP2_MAGIC: &[u8] = b"BZh";
+
+/// The packages of a sync database.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SyncDb {
+    name: String,
+    packages: Vec<Package>,
+}
+
+impl SyncDb {
+    /// Reads a sync database from its file, naming it after the file without
+    /// its extension, such as `core` for `core.db`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Desc`] if a `desc` file is malformed, with its path
+    /// within the database.
+    ///
+    /// Returns [`Error::Io`] if the file could not be read or is not a valid
+    /// tarball.
+    ///
+    /// Returns [`Error::UnsupportedCompression`] if the database is
+    /// compressed in a format that can not be read.
+    ///
+    /// [`Error::Desc`]: ../../enum.Error.html#variant.Desc
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    /// [`Error::UnsupportedCompression`]: ../../enum.Error.html#variant.UnsupportedCompression
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        SyncDb::read(name, File::open(path)?)
+    }
+
+    /// Reads every `*.db` sync database in the `sync` directory of pacman's
+    /// database directory, such as `/var/lib/pacman`, sorted by name.
+    ///
+    /// Note that pacman uses its configured repositories in the order of its
+    /// configuration, which this does not know about.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`open`], and [`Error::Io`] if the directory
+    /// could not be read.
+    ///
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    /// [`open`]: #method.open
+    pub fn open_all<P: AsRef<Path>>(db_path: P) -> Result<Vec<Self>> {
+        let mut dbs = Vec::new();
+
+        for entry in fs::read_dir(db_path.as_ref().join("sync"))? {
+            let path = entry?.path();
+
+            if path.extension() == Some(OsStr::new("db")) {
+                dbs.push(SyncDb::open(path)?);
+            }
+        }
+
+        dbs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(dbs)
+    }
+
+    /// Reads a sync database with the given name, detecting whether it is
+    /// compressed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`open`].
+    ///
+    /// [`open`]: #method.open
+    pub fn read<S: Into<String>, R: Read>(name: S, mut reader: R) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let mut packages = if bytes.starts_with(GZIP_MAGIC) {
+            unpack(GzDecoder::new(&bytes[..]))?
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            unpack_zstd(&bytes)?
+        } else if bytes.starts_with(XZ_MAGIC) {
+            return Err(Error::UnsupportedCompression("xz"));
+        } else if bytes.starts_with(BZIP2_MAGIC) {
+            return Err(Error::UnsupportedCompression("bzip2"));
+        } else {
+            unpack(&bytes[..])?
+        };
+
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(SyncDb {
+            name: name.into(),
+            packages,
+        })
+    }
+
+    /// Returns the name of the database, such as `core`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the packages of the database, sorted by name.
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+
+    /// Returns the package with the given name, if any.
+    pub fn package(&self, name: &str) -> Option<&Package> {
+        self.packages
+            .binary_search_by(|package| package.name[..].cmp(name))
+            .ok()
+            .map(|idx| &self.packages[idx])
+    }
+
+    /// Returns the packages satisfying a dependency, either by name or by
+    /// one of their `provides`.
+    pub fn satisfiers(&self, dependency: &Dependency) -> Vec<&Package> {
+        self.packages
+            .iter()
+            .filter(|package| satisfies(package, dependency))
+            .collect()
+    }
+}
+
+/// An AUR package that is available from a sync database.
+#[derive(Clone, Debug)]
+pub struct Superseded<'a> {
+    /// The AUR package.
+    pub aur: &'a InfoResult,
+    /// The name of the sync database.
+    pub db: &'a str,
+    /// The package in the sync database.
+    pub package: &'a Package,
+    /// The entry of the sync package's `provides` naming the AUR package, or
+    /// `None` if the sync package has the same name.
+    pub provision: Option<Dependency>,
+}
+
+/// Finds the AUR packages that exist in, or are provided by a package in,
+/// the given sync databases.
+///
+/// Databases are searched in order, and a package with the same name is
+/// preferred over a package providing it. Each AUR package is reported at
+/// most once, in the order given.
+pub fn superseded<'a, I>(packages: I, dbs: &'a [SyncDb]) -> Vec<Superseded<'a>>
+    where I: IntoIterator<Item = &'a InfoResult> {
+    packages.into_iter().filter_map(|aur| {
+        let by_name = dbs.iter().filter_map(|db| {
+            db.package(&aur.name).map(|package| Superseded {
+                aur,
+                db: &db.name,
+                package,
+                provision: None,
+            })
+        }).next();
+
+        by_name.or_else(|| {
+            let dependency = Dependency::parse(&aur.name);
+
+            dbs.iter().filter_map(|db| {
+                db.packages.iter().filter_map(|package| {
+                    provision(package, &dependency).map(|provision| Superseded {
+                        aur,
+                        db: &db.name,
+                        package,
+                        provision: Some(provision),
+                    })
+                }).next()
+            }).next()
+        })
+    }).collect()
+}
+
+/// Whether a package satisfies a dependency by name or provision.
+fn satisfies(package: &Package, dependency: &Dependency) -> bool {
+    dependency.is_satisfied_by(&package.name, &package.parsed_version())
+        || provision(package, dependency).is_some()
+}
+
+/// Returns the entry of a package's `provides` satisfying a dependency.
+fn provision(package: &Package, dependency: &Dependency) -> Option<Dependency> {
+    package.provides
+        .iter()
+        .map(|provision| Dependency::parse(provision))
+        .find(|provision| dependency.is_satisfied_by_provision(provision))
+}
+
+/// Parses every `desc` file of an uncompressed database tarball.
+fn unpack<R: Read>(reader: R) -> Result<Vec<Package>> {
+    let mut archive = Archive::new(reader);
+    let mut packages = Vec::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+
+        if path.file_name() != Some(OsStr::new("desc")) {
+            continue;
+        }
+
+        let mut text = String::new();
+        entry.read_to_string(&mut text)?;
+
+        packages.push(Package::parse_at(&text, &path)?);
+    }
+
+    Ok(packages)
+}
+
+/// Parses every `desc` file of a zstd-compressed database tarball.
+#[cfg(feature = "zstd")]
+fn unpack_zstd(bytes: &[u8]) -> Result<Vec<Package>> {
+    unpack(::zstd::Decoder::new(bytes)?)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn unpack_zstd(_: &[u8]) -> Result<Vec<Package>> {
+    Err(Error::UnsupportedCompression("zstd"))
+}
@@ -0,0 +1,1099 @@
+//! Static extraction of variables from PKGBUILDs.
+//!
+//! A PKGBUILD is a bash script, and sourcing one runs arbitrary code. A
+//! [`Pkgbuild`] instead reads the top-level variable assignments of the script
+//! without executing anything, evaluating the subset of bash needed for
+//! typical metadata:
+//!
+//! - scalar and array assignments, including `+=`;
+//! - single quotes, double quotes, and backslash escapes;
+//! - line continuations;
+//! - `$name`, `${name}`, `${name[@]}`, `${name[*]}`, and `${name[N]}`
+//!   expansions of previously assigned variables.
+//!
+//! Assignments prefixing a command, such as `CFLAGS=-O2 make`, only apply to
+//! that command as in bash, so they are not recorded.
+//!
+//! Anything else is skipped and reported as an [`Issue`], such as command
+//! substitutions, function definitions, control flow, and other forms of
+//! parameter expansion. An assignment that cannot be evaluated is not
+//! recorded at all, rather than being recorded with a partial value.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aur::pkgbuild::{IssueKind, Pkgbuild};
+//!
+//! let pkgbuild = Pkgbuild::parse(r#"
+//! pkgname=example
+//! pkgver=1.2.3
+//! pkgrel=1
+//! arch=('x86_64')
+//! depends=('glibc'
+//!          'zlib')
+//! source=("https://example.org/$pkgname-$pkgver.tar.gz")
+//! _commit=$(git rev-parse HEAD)
+//!
+//! package() {
+//!     make DESTDIR="$pkgdir" install
+//! }
+//! "#);
+//!
+//! assert_eq!(pkgbuild.pkgnames(), ["example"]);
+//! assert_eq!(pkgbuild.depends(), ["glibc", "zlib"]);
+//! assert_eq!(pkgbuild.source(), ["https://example.org/example-1.2.3.tar.gz"]);
+//!
+//! assert_eq!(pkgbuild.issues[0].kind, IssueKind::CommandSubstitution);
+//! assert_eq!(pkgbuild.issues[1].kind, IssueKind::Function("package".to_owned()));
+//! ```
+//!
+//! [`Issue`]: struct.Issue.html
+//! [`Pkgbuild`]: struct.Pkgbuild.html
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Keywords opening a compound command, which is skipped as a whole.
+const OPENERS: &[&str] = &["case", "for", "if", "select", "until", "while"];
+
+/// Keywords closing a compound command.
+const CLOSERS: &[&str] = &["done", "esac", "fi"];
+
+/// The value of a variable.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Value {
+    /// An array, such as `depends=('glibc' 'zlib')`.
+    Array(Vec<String>),
+    /// A scalar, such as `pkgver=1.2.3`.
+    Scalar(String),
+}
+
+impl Value {
+    /// Returns the value as a scalar, which is the first element of an array
+    /// as in bash.
+    pub fn as_scalar(&self) -> Option<&str> {
+        match *self {
+            Value::Array(ref values) => values.first().map(|value| &value[..]),
+            Value::Scalar(ref value) => Some(value),
+        }
+    }
+
+    /// Returns the value as a list, which is a single element for a scalar
+    /// as in bash.
+    pub fn as_array(&self) -> Vec<&str> {
+        match *self {
+            Value::Array(ref values) => values.iter().map(|value| &value[..]).collect(),
+            Value::Scalar(ref value) => vec![value],
+        }
+    }
+}
+
+/// Something in a PKGBUILD that was not evaluated, with the line it starts
+/// on.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Issue {
+    /// The kind of issue.
+    pub kind: IssueKind,
+    /// The number of the line the issue is on, starting at 1.
+    pub line: usize,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "line {}: ", self.line)?;
+
+        match self.kind {
+            IssueKind::CommandSubstitution => f.write_str("command substitution not evaluated"),
+            IssueKind::Function(ref name) => write!(f, "function {} not evaluated", name),
+            IssueKind::Statement(ref word) => write!(f, "statement {} not evaluated", word),
+            IssueKind::Syntax(ref token) => write!(f, "unexpected {}", token),
+            IssueKind::UndefinedVariable(ref name) => write!(f, "undefined variable {}", name),
+            IssueKind::UnsupportedExpansion(ref text) => {
+                write!(f, "expansion {} not evaluated", text)
+            },
+            IssueKind::Unterminated => f.write_str("unterminated quote or array"),
+        }
+    }
+}
+
+/// The kind of an [`Issue`].
+///
+/// [`Issue`]: struct.Issue.html
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum IssueKind {
+    /// A command substitution, such as `$(date)` or `` `date` ``. The
+    /// assignment containing it is not recorded.
+    CommandSubstitution,
+    /// A function definition, such as `package()`, containing its name. The
+    /// body of the function is skipped, including any assignments in it.
+    Function(String),
+    /// A statement that is not an assignment, such as a command or an `if`,
+    /// containing its first word as written. The statement is skipped.
+    Statement(String),
+    /// An operator where bash does not allow one, such as the `|` in
+    /// `depends=('a' | 'b')`, containing it. The assignment containing it is
+    /// not recorded.
+    Syntax(String),
+    /// An expansion of a variable that was not assigned before it, containing
+    /// its name. It expands to nothing, as in bash, such as for `$srcdir`.
+    UndefinedVariable(String),
+    /// A form of expansion that is not supported, such as `${name/a/b}` or
+    /// arithmetic, containing its text. The assignment containing it is not
+    /// recorded.
+    UnsupportedExpansion(String),
+    /// A quote or array that is not closed before the end of the file.
+    Unterminated,
+}
+
+/// The variables statically extracted from a PKGBUILD.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Pkgbuild {
+    /// Everything in the PKGBUILD that was not evaluated, in order.
+    ///
+    /// The `build` and `package` functions of any PKGBUILD are reported
+    /// here, along with anything else that may affect the variables.
+    pub issues: Vec<Issue>,
+    variables: BTreeMap<String, Value>,
+}
+
+impl Pkgbuild {
+    /// Reads the variables assigned at the top level of a PKGBUILD.
+    ///
+    /// This never fails; anything that cannot be evaluated is reported in
+    /// [`issues`].
+    ///
+    /// [`issues`]: #structfield.issues
+    pub fn parse(text: &str) -> Self {
+        let mut parser = Parser {
+            chars: text.chars().collect(),
+            line: 1,
+            pkgbuild: Pkgbuild::default(),
+            pos: 0,
+        };
+
+        parser.run();
+
+        parser.pkgbuild
+    }
+
+    /// Returns the value of a variable.
+    pub fn variable(&self, name: &str) -> Option<&Value> {
+        self.variables.get(name)
+    }
+
+    /// Returns every variable by name.
+    pub fn variables(&self) -> &BTreeMap<String, Value> {
+        &self.variables
+    }
+
+    /// Returns the value of a variable as a scalar.
+    pub fn scalar(&self, name: &str) -> Option<&str> {
+        self.variable(name).and_then(Value::as_scalar)
+    }
+
+    /// Returns the value of a variable as a list, which is empty if the
+    /// variable is not assigned.
+    pub fn array(&self, name: &str) -> Vec<&str> {
+        self.variable(name).map(Value::as_array).unwrap_or_default()
+    }
+
+    /// Returns the `pkgbase`, if assigned.
+    pub fn pkgbase(&self) -> Option<&str> {
+        self.scalar("pkgbase")
+    }
+
+    /// Returns the names of the packages, from `pkgname`.
+    pub fn pkgnames(&self) -> Vec<&str> {
+        self.array("pkgname")
+    }
+
+    /// Returns the `pkgver`, if assigned.
+    pub fn pkgver(&self) -> Option<&str> {
+        self.scalar("pkgver")
+    }
+
+    /// Returns the `pkgrel`, if assigned.
+    pub fn pkgrel(&self) -> Option<&str> {
+        self.scalar("pkgrel")
+    }
+
+    /// Returns the `epoch`, if assigned.
+    pub fn epoch(&self) -> Option<&str> {
+        self.scalar("epoch")
+    }
+
+    /// Returns the architectures, from `arch`.
+    pub fn arch(&self) -> Vec<&str> {
+        self.array("arch")
+    }
+
+    /// Returns the runtime dependencies shared by every architecture, from
+    /// `depends`.
+    ///
+    /// Architecture-specific values are available through [`array`], such as
+    /// `array("depends_x86_64")`.
+    ///
+    /// [`array`]: #method.array
+    pub fn depends(&self) -> Vec<&str> {
+        self.array("depends")
+    }
+
+    /// Returns the build dependencies shared by every architecture, from
+    /// `makedepends`.
+    pub fn make_depends(&self) -> Vec<&str> {
+        self.array("makedepends")
+    }
+
+    /// Returns the sources shared by every architecture, from `source`.
+    pub fn source(&self) -> Vec<&str> {
+        self.array("source")
+    }
+
+    /// Returns the checksums of the sources shared by every architecture for
+    /// an algorithm, such as `"sha256"` for `sha256sums`.
+    pub fn checksums(&self, algorithm: &str) -> Vec<&str> {
+        self.array(&format!("{}sums", algorithm))
+    }
+}
+
+/// The result of evaluating a word.
+enum Word {
+    /// The fields that the word expanded to.
+    Fields(Vec<String>),
+    /// The word contained something that cannot be evaluated, which has been
+    /// reported.
+    Failed,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    line: usize,
+    pkgbuild: Pkgbuild,
+    pos: usize,
+}
+
+impl Parser {
+    fn run(&mut self) {
+        loop {
+            self.skip_blanks(true);
+
+            if self.peek().is_none() {
+                break;
+            }
+
+            let line = self.line;
+
+            if let Some((name, append)) = self.assignment_name() {
+                self.assignments(name, append);
+
+                continue;
+            }
+
+            let start = self.pos;
+
+            let word = match self.raw_word() {
+                Some(word) => word,
+                None => {
+                    // A lone operator, such as a stray `)`.
+                    self.next();
+
+                    continue;
+                },
+            };
+
+            // The statement is reported as written, as a word that is quoted
+            // or substituted has no plain text.
+            let text = self.chars[start..self.pos].iter().collect::<String>();
+
+            self.skip_blanks(false);
+
+            if word == "function" {
+                let name = self.raw_word().unwrap_or_default();
+                self.skip_function(name, line);
+            } else if self.peek() == Some('(') && self.peek_at(1) == Some(')') {
+                self.pos += 2;
+                self.skip_function(word, line);
+            } else if OPENERS.contains(&&word[..]) {
+                self.skip_compound();
+                self.issue(IssueKind::Statement(text), line);
+            } else {
+                self.skip_statement();
+                self.issue(IssueKind::Statement(text), line);
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+
+        if c == '\n' {
+            self.line += 1;
+        }
+
+        Some(c)
+    }
+
+    fn issue(&mut self, kind: IssueKind, line: usize) {
+        self.pkgbuild.issues.push(Issue {
+            kind,
+            line,
+        });
+    }
+
+    /// Whether the character before the one just consumed is whitespace or
+    /// the start of the file, meaning that a `#` just consumed starts a
+    /// comment.
+    fn follows_blank(&self) -> bool {
+        match self.pos.checked_sub(2).and_then(|pos| self.chars.get(pos)) {
+            Some(c) => c.is_whitespace(),
+            None => true,
+        }
+    }
+
+    /// Skips the rest of a comment.
+    fn skip_comment(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+
+            self.next();
+        }
+    }
+
+    /// Skips whitespace, comments, and line continuations, and optionally
+    /// newlines and semicolons.
+    fn skip_blanks(&mut self, newlines: bool) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' => {
+                    self.next();
+                },
+                '\n' | ';' if newlines => {
+                    self.next();
+                },
+                '\\' if self.peek_at(1) == Some('\n') => {
+                    self.next();
+                    self.next();
+                },
+                '#' => self.skip_comment(),
+                _ => break,
+            }
+        }
+    }
+
+    /// Consumes the name and operator of an assignment, such as `depends+=`,
+    /// if one starts at the current position.
+    fn assignment_name(&mut self) -> Option<(String, bool)> {
+        let start = self.pos;
+        let mut end = start;
+
+        while let Some(&c) = self.chars.get(end) {
+            let valid = c == '_' || c.is_ascii_alphabetic() || (end > start && c.is_ascii_digit());
+
+            if !valid {
+                break;
+            }
+
+            end += 1;
+        }
+
+        if end == start {
+            return None;
+        }
+
+        let (append, len) = match (self.chars.get(end), self.chars.get(end + 1)) {
+            (Some(&'='), _) => (false, 1),
+            (Some(&'+'), Some(&'=')) => (true, 2),
+            _ => return None,
+        };
+
+        let name = self.chars[start..end].iter().collect();
+        self.pos = end + len;
+
+        Some((name, append))
+    }
+
+    /// Evaluates the assignments starting a statement, such as `a=1 b=2`.
+    ///
+    /// If a command follows them, as in `CFLAGS=-O2 make`, they only apply
+    /// to that command, so the variables are restored and the command is left
+    /// to be reported as a statement.
+    fn assignments(&mut self, mut name: String, mut append: bool) {
+        let variables = self.pkgbuild.variables.clone();
+
+        loop {
+            self.assignment(name, append);
+            self.skip_blanks(false);
+
+            match self.assignment_name() {
+                Some((next, next_append)) => {
+                    name = next;
+                    append = next_append;
+                },
+                None => break,
+            }
+        }
+
+        loop {
+            match (self.peek(), self.peek_at(1)) {
+                (None, _) | (Some('\n'), _) | (Some(';'), _) => return,
+                (Some('&'), Some('&')) | (Some('|'), Some('|')) => return,
+                _ => {},
+            }
+
+            if !self.skip_redirection() {
+                break;
+            }
+
+            self.skip_blanks(false);
+        }
+
+        self.pkgbuild.variables = variables;
+    }
+
+    /// Skips a redirection and its target, such as `2>/dev/null`, if one
+    /// starts at the current position.
+    fn skip_redirection(&mut self) -> bool {
+        let mut end = self.pos;
+
+        while self.chars.get(end).is_some_and(char::is_ascii_digit) {
+            end += 1;
+        }
+
+        match (self.chars.get(end), self.chars.get(end + 1)) {
+            (Some(&'<'), _) | (Some(&'>'), _) => {},
+            (Some(&'&'), Some(&'>')) if end == self.pos => {},
+            _ => return false,
+        }
+
+        while self.chars.get(end).is_some_and(|&c| "<>&|".contains(c)) {
+            end += 1;
+        }
+
+        self.pos = end;
+        self.skip_blanks(false);
+        self.raw_word();
+
+        true
+    }
+
+    fn assignment(&mut self, name: String, append: bool) {
+        let value = if self.peek() == Some('(') {
+            self.next();
+
+            self.array().map(Value::Array)
+        } else {
+            match self.word(false) {
+                Word::Fields(mut fields) => {
+                    Some(Value::Scalar(fields.pop().unwrap_or_default()))
+                },
+                Word::Failed => None,
+            }
+        };
+
+        let value = match value {
+            Some(value) => value,
+            None => return,
+        };
+
+        let variables = &mut self.pkgbuild.variables;
+
+        let value = match (variables.remove(&name), value) {
+            (Some(Value::Scalar(mut old)), Value::Scalar(new)) if append => {
+                old.push_str(&new);
+
+                Value::Scalar(old)
+            },
+            (Some(Value::Array(mut old)), Value::Array(new)) if append => {
+                old.extend(new);
+
+                Value::Array(old)
+            },
+            (Some(Value::Array(mut old)), Value::Scalar(new)) if append => {
+                // Appending to an array without parentheses appends to its
+                // first element.
+                match old.first_mut() {
+                    Some(first) => first.push_str(&new),
+                    None => old.push(new),
+                }
+
+                Value::Array(old)
+            },
+            (Some(Value::Scalar(old)), Value::Array(new)) if append => {
+                let mut values = vec![old];
+                values.extend(new);
+
+                Value::Array(values)
+            },
+            (_, value) => value,
+        };
+
+        variables.insert(name, value);
+    }
+
+    /// Evaluates the elements of an array up to its closing parenthesis.
+    fn array(&mut self) -> Option<Vec<String>> {
+        let line = self.line;
+        let mut values = Vec::new();
+        let mut failed = false;
+
+        loop {
+            self.skip_blanks(true);
+
+            match self.peek() {
+                Some(')') => {
+                    self.next();
+
+                    break;
+                },
+                Some(c) if "&|<>(".contains(c) => {
+                    // Nothing is consumed by `word` here, so the operator is
+                    // skipped to make progress.
+                    if !failed {
+                        self.issue(IssueKind::Syntax(c.to_string()), self.line);
+                    }
+
+                    if c == '(' {
+                        self.skip_parens();
+                    } else {
+                        self.next();
+                    }
+
+                    failed = true;
+                },
+                Some(_) => match self.word(true) {
+                    Word::Fields(fields) => values.extend(fields),
+                    Word::Failed => failed = true,
+                },
+                None => {
+                    self.issue(IssueKind::Unterminated, line);
+
+                    return None;
+                },
+            }
+        }
+
+        if failed {
+            None
+        } else {
+            Some(values)
+        }
+    }
+
+    /// Evaluates a word, splitting the results of unquoted expansions into
+    /// separate fields if `split` is set.
+    fn word(&mut self, split: bool) -> Word {
+        let mut fields = Vec::new();
+        let mut current = String::new();
+        let mut started = false;
+        let mut failed = false;
+
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\n' | ';' | '&' | '|' | '<' | '>' | '(' | ')' => break,
+                '\\' => {
+                    self.next();
+
+                    match self.next() {
+                        Some('\n') => {},
+                        Some(c) => {
+                            current.push(c);
+                            started = true;
+                        },
+                        None => {},
+                    }
+                },
+                '\'' => {
+                    let line = self.line;
+                    self.next();
+                    started = true;
+
+                    loop {
+                        match self.next() {
+                            Some('\'') => break,
+                            Some(c) => current.push(c),
+                            None => {
+                                self.issue(IssueKind::Unterminated, line);
+
+                                return Word::Failed;
+                            },
+                        }
+                    }
+                },
+                '"' => {
+                    let line = self.line;
+                    self.next();
+                    started = true;
+
+                    loop {
+                        match self.peek() {
+                            Some('"') => {
+                                self.next();
+
+                                break;
+                            },
+                            Some('\\') => {
+                                self.next();
+
+                                match self.next() {
+                                    Some('\n') => {},
+                                    Some(c) if "$`\"\\".contains(c) => current.push(c),
+                                    Some(c) => {
+                                        current.push('\\');
+                                        current.push(c);
+                                    },
+                                    None => {},
+                                }
+                            },
+                            Some('$') => match self.expansion(true) {
+                                Some(pieces) => {
+                                    join_pieces(&mut fields, &mut current, pieces, split);
+                                },
+                                None => failed = true,
+                            },
+                            Some('`') => {
+                                self.skip_backticks();
+                                failed = true;
+                            },
+                            Some(c) => {
+                                self.next();
+                                current.push(c);
+                            },
+                            None => {
+                                self.issue(IssueKind::Unterminated, line);
+
+                                return Word::Failed;
+                            },
+                        }
+                    }
+                },
+                '$' => match self.expansion(false) {
+                    Some(pieces) => {
+                        let pieces = if split {
+                            pieces
+                                .iter()
+                                .flat_map(|piece| piece.split_whitespace())
+                                .map(ToOwned::to_owned)
+                                .collect()
+                        } else {
+                            pieces
+                        };
+
+                        started |= !pieces.is_empty();
+                        join_pieces(&mut fields, &mut current, pieces, split);
+                    },
+                    None => failed = true,
+                },
+                '`' => {
+                    self.skip_backticks();
+                    failed = true;
+                },
+                c => {
+                    self.next();
+                    current.push(c);
+                    started = true;
+                },
+            }
+        }
+
+        if failed {
+            return Word::Failed;
+        }
+
+        if started || !current.is_empty() {
+            fields.push(current);
+        }
+
+        Word::Fields(fields)
+    }
+
+    /// Evaluates an expansion starting with `$`, returning the pieces it
+    /// expands to, which are separate fields in an array.
+    ///
+    /// Returns `None` if the expansion cannot be evaluated, after reporting
+    /// it.
+    fn expansion(&mut self, quoted: bool) -> Option<Vec<String>> {
+        let line = self.line;
+        self.next();
+
+        match self.peek() {
+            Some('(') => {
+                let arithmetic = self.peek_at(1) == Some('(');
+                self.skip_parens();
+
+                let kind = if arithmetic {
+                    IssueKind::UnsupportedExpansion("$((...))".to_owned())
+                } else {
+                    IssueKind::CommandSubstitution
+                };
+                self.issue(kind, line);
+
+                None
+            },
+            Some('{') => {
+                self.next();
+                let mut inner = String::new();
+                let mut depth = 0;
+
+                // Nested expansions, such as in `${a:-${b}}`, are part of the
+                // text up to the matching brace.
+                loop {
+                    match self.next() {
+                        Some('}') if depth == 0 => break,
+                        Some('}') => {
+                            depth -= 1;
+                            inner.push('}');
+                        },
+                        Some('$') if self.peek() == Some('{') => {
+                            self.next();
+                            depth += 1;
+                            inner.push_str("${");
+                        },
+                        Some('\\') => {
+                            inner.push('\\');
+                            inner.extend(self.next());
+                        },
+                        Some(c) => inner.push(c),
+                        None => {
+                            self.issue(IssueKind::Unterminated, line);
+
+                            return None;
+                        },
+                    }
+                }
+
+                let expanded = self.expand_braced(&inner, quoted, line);
+
+                if expanded.is_none() {
+                    let text = format!("${{{}}}", inner);
+                    self.issue(IssueKind::UnsupportedExpansion(text), line);
+                }
+
+                expanded
+            },
+            Some(c) if c == '_' || c.is_ascii_alphabetic() => {
+                let mut name = String::new();
+
+                while let Some(c) = self.peek() {
+                    if c == '_' || c.is_ascii_alphanumeric() {
+                        name.push(c);
+                        self.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                Some(self.lookup(&name, line).into_iter().collect())
+            },
+            Some(c) if c.is_ascii_digit() || "@*#?$!-".contains(c) => {
+                self.next();
+                self.issue(IssueKind::UnsupportedExpansion(format!("${}", c)), line);
+
+                None
+            },
+            _ => Some(vec!["$".to_owned()]),
+        }
+    }
+
+    /// Evaluates the inside of a `${...}` expansion, returning `None` if it
+    /// is not a supported form.
+    ///
+    /// Within double quotes, `${name[*]}` expands to a single piece joining
+    /// the elements with spaces, while `${name[@]}` expands to a piece per
+    /// element.
+    fn expand_braced(&mut self, inner: &str, quoted: bool, line: usize)
+        -> Option<Vec<String>> {
+        let (name, index) = match inner.find('[') {
+            Some(pos) if inner.ends_with(']') => {
+                (&inner[..pos], Some(&inner[pos + 1..inner.len() - 1]))
+            },
+            Some(_) => return None,
+            None => (inner, None),
+        };
+
+        let valid = !name.is_empty()
+            && name.chars().all(|c| c == '_' || c.is_ascii_alphanumeric())
+            && !name.starts_with(|c: char| c.is_ascii_digit());
+
+        if !valid {
+            return None;
+        }
+
+        match index {
+            None => Some(self.lookup(name, line).into_iter().collect()),
+            Some(all @ "@") | Some(all @ "*") => {
+                if !self.pkgbuild.variables.contains_key(name) {
+                    self.issue(IssueKind::UndefinedVariable(name.to_owned()), line);
+                }
+
+                let values = self.pkgbuild.array(name);
+
+                if quoted && all == "*" {
+                    Some(vec![values.join(" ")])
+                } else {
+                    Some(values.into_iter().map(ToOwned::to_owned).collect())
+                }
+            },
+            Some(index) => {
+                let index = index.parse::<usize>().ok()?;
+
+                let value = match self.pkgbuild.variable(name) {
+                    Some(value) => match *value {
+                        Value::Array(ref values) => values.get(index).cloned(),
+                        Value::Scalar(ref value) if index == 0 => Some(value.clone()),
+                        Value::Scalar(_) => None,
+                    },
+                    None => {
+                        self.issue(IssueKind::UndefinedVariable(name.to_owned()), line);
+
+                        None
+                    },
+                };
+
+                Some(value.into_iter().collect())
+            },
+        }
+    }
+
+    /// Looks up the scalar value of a variable, reporting it if undefined.
+    fn lookup(&mut self, name: &str, line: usize) -> Option<String> {
+        match self.pkgbuild.scalar(name) {
+            Some(value) => Some(value.to_owned()),
+            None => {
+                if !self.pkgbuild.variables.contains_key(name) {
+                    self.issue(IssueKind::UndefinedVariable(name.to_owned()), line);
+                }
+
+                None
+            },
+        }
+    }
+
+    /// Consumes a word without evaluating it, returning its unquoted text, or
+    /// `None` if there is no word at the current position.
+    ///
+    /// Quoted parts and substitutions are not part of the text, so the text
+    /// of a word such as `"$(id)"` is empty. Command substitutions are
+    /// reported, as they run when the word is evaluated.
+    fn raw_word(&mut self) -> Option<String> {
+        let start = self.pos;
+        let mut word = String::new();
+
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\n' | ';' | '&' | '|' | '<' | '>' | '(' | ')' => break,
+                '\'' => {
+                    self.next();
+
+                    while let Some(inner) = self.next() {
+                        if inner == c {
+                            break;
+                        }
+                    }
+                },
+                '"' => {
+                    self.next();
+
+                    while let Some(inner) = self.peek() {
+                        match inner {
+                            '"' => {
+                                self.next();
+
+                                break;
+                            },
+                            '\\' => {
+                                self.next();
+                                self.next();
+                            },
+                            '$' if self.peek_at(1) == Some('(') => self.skip_substitution(),
+                            '`' => self.skip_backticks(),
+                            _ => {
+                                self.next();
+                            },
+                        }
+                    }
+                },
+                '\\' => {
+                    self.next();
+                    self.next();
+                },
+                '$' if self.peek_at(1) == Some('(') => self.skip_substitution(),
+                '`' => self.skip_backticks(),
+                c => {
+                    self.next();
+                    word.push(c);
+                },
+            }
+        }
+
+        if self.pos == start {
+            None
+        } else {
+            Some(word)
+        }
+    }
+
+    /// Skips a `$(` command substitution, reporting it, or an arithmetic
+    /// expansion.
+    fn skip_substitution(&mut self) {
+        let line = self.line;
+        let arithmetic = self.peek_at(2) == Some('(');
+
+        self.next();
+        self.skip_parens();
+
+        if !arithmetic {
+            self.issue(IssueKind::CommandSubstitution, line);
+        }
+    }
+
+    /// Skips a balanced pair of parentheses starting at the current position.
+    fn skip_parens(&mut self) {
+        self.skip_balanced('(', ')');
+    }
+
+    /// Skips a balanced pair of delimiters, ignoring delimiters within
+    /// quotes.
+    fn skip_balanced(&mut self, open: char, close: char) {
+        let mut depth = 0;
+
+        while let Some(c) = self.next() {
+            match c {
+                '\\' => {
+                    self.next();
+                },
+                '\'' | '"' => {
+                    while let Some(inner) = self.next() {
+                        if inner == c {
+                            break;
+                        }
+
+                        if inner == '\\' && c == '"' {
+                            self.next();
+                        }
+                    }
+                },
+                '#' if self.follows_blank() => self.skip_comment(),
+                c if c == open => depth += 1,
+                c if c == close => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        break;
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn skip_backticks(&mut self) {
+        let line = self.line;
+        self.next();
+
+        while let Some(c) = self.next() {
+            match c {
+                '\\' => {
+                    self.next();
+                },
+                '`' => break,
+                _ => {},
+            }
+        }
+
+        self.issue(IssueKind::CommandSubstitution, line);
+    }
+
+    /// Skips the body of a function whose name has been consumed.
+    fn skip_function(&mut self, name: String, line: usize) {
+        self.skip_blanks(true);
+
+        // `function name() {` may have the parentheses after the name.
+        if self.peek() == Some('(') && self.peek_at(1) == Some(')') {
+            self.pos += 2;
+            self.skip_blanks(true);
+        }
+
+        match self.peek() {
+            Some('{') => self.skip_balanced('{', '}'),
+            Some('(') => self.skip_parens(),
+            _ => self.skip_statement(),
+        }
+
+        self.issue(IssueKind::Function(name), line);
+    }
+
+    /// Skips a compound command whose opening keyword has been consumed.
+    fn skip_compound(&mut self) {
+        let mut depth = 1;
+
+        while depth > 0 {
+            self.skip_blanks(true);
+
+            if self.peek().is_none() {
+                break;
+            }
+
+            match self.raw_word() {
+                Some(ref word) if OPENERS.contains(&&word[..]) => depth += 1,
+                Some(ref word) if CLOSERS.contains(&&word[..]) => depth -= 1,
+                Some(_) => {},
+                None => {
+                    self.next();
+                },
+            }
+        }
+    }
+
+    /// Skips the rest of a simple statement.
+    fn skip_statement(&mut self) {
+        loop {
+            self.skip_blanks(false);
+
+            match self.peek() {
+                None | Some('\n') | Some(';') => break,
+                Some(_) => {
+                    if self.raw_word().is_none() {
+                        self.next();
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Appends the pieces of an expansion to the word being evaluated, with
+/// every piece after the first starting a new field if `split` is set.
+fn join_pieces(
+    fields: &mut Vec<String>,
+    current: &mut String,
+    pieces: Vec<String>,
+    split: bool,
+) {
+    if !split {
+        let joined = pieces.join(" ");
+        current.push_str(&joined);
+
+        return;
+    }
+
+    let mut pieces = pieces.into_iter();
+
+    if let Some(first) = pieces.next() {
+        current.push_str(&first);
+    }
+
+    for piece in pieces {
+        fields.push(::std::mem::replace(current, piece));
+    }
+}
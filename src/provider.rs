@@ -0,0 +1,141 @@
+//! Finding the packages that provide a dependency.
+//!
+//! A dependency such as `java-runtime` or `libfoo.so` often names no package
+//! at all, and is instead satisfied by any package that lists it in its
+//! `provides`. The packages providing a name can be searched for with a
+//! bridge's `aur_search_by` and [`SearchBy::Provides`], after which
+//! [`candidates`] checks their information against the dependency's version
+//! constraint and ranks them.
+//!
+//! A [`Chooser`] then picks one of the candidates, either by asking the user
+//! or by applying a policy such as [`FirstCandidate`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aur::dependency::Dependency;
+//! use aur::model::InfoResult;
+//! use aur::provider::{self, Chooser, FirstCandidate};
+//!
+//! fn choose_java(packages: &[InfoResult]) -> Option<&InfoResult> {
+//!     let dependency = Dependency::parse("java-runtime>=17");
+//!     let candidates = provider::candidates(&dependency, packages);
+//!
+//!     FirstCandidate
+//!         .choose(&dependency, &candidates)
+//!         .map(|idx| candidates[idx].package)
+//! }
+//! ```
+//!
+//! [`Chooser`]: trait.Chooser.html
+//! [`FirstCandidate`]: struct.FirstCandidate.html
+//! [`SearchBy::Provides`]: ../model/enum.SearchBy.html#variant.Provides
+//! [`candidates`]: fn.candidates.html
+
+use dependency::Dependency;
+use model::InfoResult;
+use std::cmp::Ordering;
+use version::Version;
+
+/// A package satisfying a dependency.
+#[derive(Clone, Debug)]
+pub struct Candidate<'a> {
+    /// The satisfying package.
+    pub package: &'a InfoResult,
+    /// The entry of the package's `provides` satisfying the dependency, or
+    /// `None` if the package satisfies it by name.
+    pub provision: Option<Dependency>,
+}
+
+impl<'a> Candidate<'a> {
+    /// Checks whether a package satisfies a dependency, either by its own
+    /// name and version or by one of its `provides`.
+    pub fn new(package: &'a InfoResult, dependency: &Dependency) -> Option<Self> {
+        let version = Version::parse(&package.version);
+
+        if dependency.is_satisfied_by(&package.name, &version) {
+            return Some(Candidate {
+                package,
+                provision: None,
+            });
+        }
+
+        package.parsed_provides().into_iter().find(|provision| {
+            dependency.is_satisfied_by_provision(provision)
+        }).map(|provision| Candidate {
+            package,
+            provision: Some(provision),
+        })
+    }
+
+    /// Whether the package satisfies the dependency by name rather than by
+    /// a provision.
+    pub fn is_exact(&self) -> bool {
+        self.provision.is_none()
+    }
+}
+
+/// Returns the packages satisfying a dependency, best first.
+///
+/// A package named after the dependency ranks first. The rest are ranked
+/// with up-to-date packages before out-of-date ones, maintained packages
+/// before orphaned ones, and then by votes, popularity, and name.
+pub fn candidates<'a, I>(dependency: &Dependency, packages: I) -> Vec<Candidate<'a>>
+    where I: IntoIterator<Item = &'a InfoResult> {
+    let mut candidates = packages
+        .into_iter()
+        .filter_map(|package| Candidate::new(package, dependency))
+        .collect::<Vec<_>>();
+
+    candidates.sort_by(rank);
+
+    candidates
+}
+
+/// Orders two candidates from best to worst.
+fn rank(a: &Candidate, b: &Candidate) -> Ordering {
+    b.is_exact().cmp(&a.is_exact())
+        .then_with(|| a.package.is_out_of_date().cmp(&b.package.is_out_of_date()))
+        .then_with(|| a.package.is_orphaned().cmp(&b.package.is_orphaned()))
+        .then_with(|| b.package.num_votes.cmp(&a.package.num_votes))
+        .then_with(|| {
+            b.package.popularity
+                .partial_cmp(&a.package.popularity)
+                .unwrap_or(Ordering::Equal)
+        })
+        .then_with(|| a.package.name.cmp(&b.package.name))
+}
+
+/// Picks which of several candidates should satisfy a dependency.
+///
+/// This is implemented for closures taking the dependency and the ranked
+/// candidates, so that interactive tools can ask the user which to pick.
+pub trait Chooser {
+    /// Returns the index of the chosen candidate, or `None` to leave the
+    /// dependency unsatisfied.
+    ///
+    /// This is only called with at least one candidate.
+    fn choose(&mut self, dependency: &Dependency, candidates: &[Candidate]) -> Option<usize>;
+}
+
+impl<F> Chooser for F where F: FnMut(&Dependency, &[Candidate]) -> Option<usize> {
+    fn choose(&mut self, dependency: &Dependency, candidates: &[Candidate]) -> Option<usize> {
+        self(dependency, candidates)
+    }
+}
+
+/// A [`Chooser`] always picking the best ranked candidate.
+///
+/// [`Chooser`]: trait.Chooser.html
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FirstCandidate;
+
+impl Chooser for FirstCandidate {
+    fn choose(&mut self, _: &Dependency, candidates: &[Candidate]) -> Option<usize> {
+        if candidates.is_empty() {
+            None
+        } else {
+            Some(0)
+        }
+    }
+}
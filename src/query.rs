@@ -0,0 +1,216 @@
+//! Client-side filtering and sorting of results.
+//!
+//! The RPC interface returns results unsorted and only supports filtering by
+//! the query itself. A [`Query`] filters and sorts any kind of result
+//! implementing [`Package`] after it has been received.
+//!
+//! # Examples
+//!
+//! Find maintained, up-to-date packages with at least 10 votes, most voted
+//! first:
+//!
+//! ```rust
+//! use aur::model::{Search, SearchResult};
+//! use aur::query::{Order, Query, SortKey};
+//!
+//! fn popular(search: &Search<SearchResult>) -> Vec<&SearchResult> {
+//!     let query = Query::new()
+//!         .orphaned(false)
+//!         .out_of_date(false)
+//!         .min_votes(10)
+//!         .sort_by(SortKey::Votes, Order::Descending);
+//!
+//!     search.query(&query)
+//! }
+//! ```
+//!
+//! [`Package`]: ../model/trait.Package.html
+//! [`Query`]: struct.Query.html
+
+use model::{Package, Search};
+use std::cmp::Ordering;
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+/// A key to sort results by.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SortKey {
+    /// Sort by when the package was first submitted.
+    FirstSubmitted,
+    /// Sort by when the package was last modified.
+    LastModified,
+    /// Sort by the name of the package.
+    Name,
+    /// Sort by the relative popularity of the package.
+    Popularity,
+    /// Sort by the number of votes that the package has.
+    Votes,
+}
+
+impl SortKey {
+    /// Compares two packages by the key, in ascending order.
+    pub fn compare<P: Package>(&self, a: &P, b: &P) -> Ordering {
+        match *self {
+            SortKey::FirstSubmitted => a.first_submitted().cmp(&b.first_submitted()),
+            SortKey::LastModified => a.last_modified().cmp(&b.last_modified()),
+            SortKey::Name => a.name().cmp(b.name()),
+            SortKey::Popularity => a.popularity()
+                .partial_cmp(&b.popularity())
+                .unwrap_or(Ordering::Equal),
+            SortKey::Votes => a.num_votes().cmp(&b.num_votes()),
+        }
+    }
+}
+
+/// The order to sort results in.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Order {
+    /// Sort from the smallest to the largest value.
+    Ascending,
+    /// Sort from the largest to the smallest value.
+    Descending,
+}
+
+/// A set of filters and a sort order to apply to results.
+///
+/// All filters must match for a result to be included. By default, nothing
+/// is filtered and results keep the order they were received in.
+#[derive(Clone, Debug, Default)]
+pub struct Query {
+    maintainer: Option<String>,
+    min_votes: Option<u64>,
+    #[cfg(feature = "regex")]
+    name_regex: Option<Regex>,
+    orphaned: Option<bool>,
+    out_of_date: Option<bool>,
+    sort: Option<(SortKey, Order)>,
+}
+
+impl Query {
+    /// Creates a query that matches every result.
+    pub fn new() -> Self {
+        Query::default()
+    }
+
+    /// Only matches packages maintained by the given user.
+    pub fn maintainer<S: Into<String>>(mut self, maintainer: S) -> Self {
+        self.maintainer = Some(maintainer.into());
+
+        self
+    }
+
+    /// Only matches packages with at least the given number of votes.
+    pub fn min_votes(mut self, votes: u64) -> Self {
+        self.min_votes = Some(votes);
+
+        self
+    }
+
+    /// Only matches packages with a name matching the regular expression.
+    ///
+    /// This is only available with the `regex` feature enabled.
+    #[cfg(feature = "regex")]
+    pub fn name_regex(mut self, regex: Regex) -> Self {
+        self.name_regex = Some(regex);
+
+        self
+    }
+
+    /// Only matches packages that either are or are not orphaned.
+    pub fn orphaned(mut self, orphaned: bool) -> Self {
+        self.orphaned = Some(orphaned);
+
+        self
+    }
+
+    /// Only matches packages that either are or are not flagged as
+    /// out-of-date.
+    pub fn out_of_date(mut self, out_of_date: bool) -> Self {
+        self.out_of_date = Some(out_of_date);
+
+        self
+    }
+
+    /// Sorts matching packages by the given key and order.
+    ///
+    /// The sort is stable, so packages comparing equal keep the order they
+    /// were received in.
+    pub fn sort_by(mut self, key: SortKey, order: Order) -> Self {
+        self.sort = Some((key, order));
+
+        self
+    }
+
+    /// Whether a package matches every filter of the query.
+    pub fn matches<P: Package>(&self, package: &P) -> bool {
+        if let Some(ref maintainer) = self.maintainer {
+            if package.maintainer() != Some(&maintainer[..]) {
+                return false;
+            }
+        }
+
+        if let Some(min_votes) = self.min_votes {
+            if package.num_votes() < min_votes {
+                return false;
+            }
+        }
+
+        #[cfg(feature = "regex")]
+        {
+            if let Some(ref regex) = self.name_regex {
+                if !regex.is_match(package.name()) {
+                    return false;
+                }
+            }
+        }
+
+        if let Some(orphaned) = self.orphaned {
+            if package.is_orphaned() != orphaned {
+                return false;
+            }
+        }
+
+        if let Some(out_of_date) = self.out_of_date {
+            if package.is_out_of_date() != out_of_date {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Applies the query to a list of packages, returning the matching
+    /// packages in sorted order.
+    pub fn apply<'a, P: Package>(&self, packages: &'a [P]) -> Vec<&'a P> {
+        let mut matches = packages
+            .iter()
+            .filter(|package| self.matches(*package))
+            .collect::<Vec<_>>();
+
+        if let Some((key, order)) = self.sort {
+            matches.sort_by(|a, b| {
+                let ordering = key.compare(*a, *b);
+
+                match order {
+                    Order::Ascending => ordering,
+                    Order::Descending => ordering.reverse(),
+                }
+            });
+        }
+
+        matches
+    }
+}
+
+impl<T: Package + Send + Sync> Search<T> {
+    /// Applies a query to the results, returning the matching results in
+    /// sorted order.
+    ///
+    /// Refer to the [`query`] module for an example.
+    ///
+    /// [`query`]: ../query/index.html
+    pub fn query(&self, query: &Query) -> Vec<&T> {
+        query.apply(&self.results)
+    }
+}
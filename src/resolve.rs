@@ -0,0 +1,566 @@
+//! Recursive resolution of AUR dependencies into a build order.
+//!
+//! A [`Resolver`] looks up a set of target packages and everything they
+//! depend on, requesting the information of each level of dependencies in as
+//! few batches as possible. Dependencies that a caller-supplied predicate
+//! considers satisfied, such as those available from the official
+//! repositories or already installed, are not looked up on the AUR.
+//!
+//! A dependency that no package is named after, such as `java-runtime`, can
+//! be resolved to a package providing it by configuring the resolver with a
+//! search for providers and a [`Chooser`].
+//!
+//! The resolved packages are grouped into [`Base`]s and sorted so that each
+//! base comes after every base it depends on, so that a base is built once
+//! even when several of its split packages are needed.
+//!
+//! # Examples
+//!
+//! Print the order to build `yay` and its AUR dependencies in, given the
+//! packages available from the official repositories:
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "reqwest")]
+//! extern crate aur;
+//! # #[cfg(feature = "reqwest")]
+//! extern crate reqwest;
+//!
+//! # #[cfg(feature = "reqwest")]
+//! # fn try_main() -> Result<(), Box<::std::error::Error>> {
+//! #
+//! use aur::bridge::reqwest::AurRequester;
+//! use aur::resolve::Resolver;
+//! use reqwest::Client;
+//! use std::collections::HashSet;
+//!
+//! let client = Client::new();
+//! let repo = ["git", "glibc", "go", "pacman"].iter().cloned().collect::<HashSet<_>>();
+//!
+//! let resolution = Resolver::new(
+//!     |names| Ok(client.aur_info(names)?.results),
+//!     |dependency| repo.contains(&dependency.name[..]),
+//! ).resolve(&["yay"])?;
+//!
+//! for base in &resolution.build_order {
+//!     println!("{}", base.name());
+//! }
+//! #     Ok(())
+//! # }
+//! #
+//! # #[cfg(not(feature = "reqwest"))]
+//! # fn try_main() -> Result<(), Box<::std::error::Error>> { Ok(()) }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`Base`]: ../base/struct.Base.html
+//! [`Chooser`]: ../provider/trait.Chooser.html
+//! [`Resolver`]: struct.Resolver.html
+
+use base::Base;
+use dependency::Dependency;
+use model::{InfoResult, SearchResult};
+use provider::{self, Candidate, Chooser};
+use std::collections::{HashMap, HashSet};
+use {Error, Result};
+
+/// The default maximum number of packages requested at once.
+const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// A function looking up the information of a batch of packages by name.
+type Fetch<'a> = Box<FnMut(&[String]) -> Result<Vec<InfoResult>> + 'a>;
+
+/// Resolves target packages and their dependencies into a build order.
+///
+/// The dependencies and make dependencies of every package are followed by
+/// default, and check dependencies can be followed as well. Optional
+/// dependencies are never followed.
+pub struct Resolver<'a> {
+    batch_size: usize,
+    check_depends: bool,
+    fetch: Fetch<'a>,
+    make_depends: bool,
+    providers: Option<Providers<'a>>,
+    satisfied: Box<Fn(&Dependency) -> bool + 'a>,
+}
+
+/// A function searching for the packages providing a name.
+type ProviderSearch<'a> = Box<FnMut(&str) -> Result<Vec<SearchResult>> + 'a>;
+
+/// The search for and choice of packages providing a dependency.
+struct Providers<'a> {
+    chooser: Box<Chooser + 'a>,
+    search: ProviderSearch<'a>,
+}
+
+impl<'a> Resolver<'a> {
+    /// Creates a resolver from a function looking up the information of a
+    /// batch of packages by name, such as with a bridge's `aur_info`, and a
+    /// predicate deciding whether a dependency is already satisfied without
+    /// the AUR.
+    ///
+    /// Packages that do not exist should be left out of the looked up
+    /// information rather than returning an error.
+    pub fn new<F, S>(fetch: F, satisfied: S) -> Self
+        where F: FnMut(&[String]) -> Result<Vec<InfoResult>> + 'a,
+              S: Fn(&Dependency) -> bool + 'a {
+        Resolver {
+            batch_size: DEFAULT_BATCH_SIZE,
+            check_depends: false,
+            fetch: Box::new(fetch),
+            make_depends: true,
+            providers: None,
+            satisfied: Box::new(satisfied),
+        }
+    }
+
+    /// Sets the maximum number of packages looked up at once, defaulting to
+    /// 100.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the batch size is zero.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch size must be positive");
+        self.batch_size = batch_size;
+
+        self
+    }
+
+    /// Sets whether check dependencies are followed, which they are not by
+    /// default.
+    pub fn check_depends(mut self, check_depends: bool) -> Self {
+        self.check_depends = check_depends;
+
+        self
+    }
+
+    /// Sets whether make dependencies are followed, which they are by
+    /// default.
+    pub fn make_depends(mut self, make_depends: bool) -> Self {
+        self.make_depends = make_depends;
+
+        self
+    }
+
+    /// Resolves dependencies that no package satisfies by name to a package
+    /// providing them, which are not resolved by default.
+    ///
+    /// The search function looks up the packages providing a name, such as
+    /// with a bridge's `aur_search_by` and [`SearchBy::Provides`]. The
+    /// information of the found packages is then looked up to check their
+    /// provisions, and the chooser picks among the ranked [`candidates`].
+    ///
+    /// Packages that are already part of the resolution and provide a
+    /// dependency are used without searching.
+    ///
+    /// [`SearchBy::Provides`]: ../model/enum.SearchBy.html#variant.Provides
+    /// [`candidates`]: ../provider/fn.candidates.html
+    pub fn providers<S, C>(mut self, search: S, chooser: C) -> Self
+        where S: FnMut(&str) -> Result<Vec<SearchResult>> + 'a,
+              C: Chooser + 'a {
+        self.providers = Some(Providers {
+            chooser: Box::new(chooser),
+            search: Box::new(search),
+        });
+
+        self
+    }
+
+    /// Resolves the given targets and their dependencies.
+    ///
+    /// Targets may carry a version constraint, such as `"foo>=2"`. They are
+    /// always looked up, even if the predicate would consider them
+    /// satisfied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DependencyCycle`] if package bases depend on each
+    /// other in a cycle.
+    ///
+    /// Returns any error returned while looking up packages.
+    ///
+    /// [`Error::DependencyCycle`]: ../enum.Error.html#variant.DependencyCycle
+    pub fn resolve<T: AsRef<str>>(&mut self, targets: &[T]) -> Result<Resolution> {
+        let mut state = State::default();
+        let mut requests = targets
+            .iter()
+            .map(|target| (Dependency::parse(target.as_ref()), None))
+            .collect::<Vec<_>>();
+
+        while !requests.is_empty() {
+            let mut found = self.fetch_level(&mut state, &requests)?;
+
+            for (dependency, required_by) in requests.drain(..) {
+                if !state.provides(&dependency)
+                    && !self.provide(&mut state, &dependency, &mut found)? {
+                    state.add_missing(dependency, required_by);
+                }
+            }
+
+            for idx in found {
+                let name = state.packages[idx].name.clone();
+
+                for dependency in self.dependencies(&state.packages[idx]) {
+                    if state.provides(&dependency) {
+                        continue;
+                    }
+
+                    if !state.index.contains_key(&dependency.name)
+                        && (self.satisfied)(&dependency) {
+                        if state.satisfied_set.insert(dependency.clone()) {
+                            state.satisfied.push(dependency);
+                        }
+                    } else {
+                        requests.push((dependency, Some(name.clone())));
+                    }
+                }
+            }
+        }
+
+        let targets = targets
+            .iter()
+            .filter_map(|target| state.resolved(&Dependency::parse(target.as_ref())))
+            .collect::<Vec<_>>();
+        let groups = self.build_order(&state, &targets)?;
+        let requested = targets
+            .iter()
+            .map(|&idx| state.packages[idx].name.clone())
+            .collect::<Vec<_>>();
+        let mut packages = state.packages.into_iter().map(Some).collect::<Vec<_>>();
+        let ordered = groups
+            .iter()
+            .flat_map(|group| group.iter())
+            .filter_map(|&idx| packages[idx].take())
+            .collect::<Vec<_>>();
+        let build_order = Base::group(ordered, &requested);
+
+        Ok(Resolution {
+            build_order,
+            missing: state.missing,
+            satisfied: state.satisfied,
+        })
+    }
+
+    /// Looks up every requested package that has not been looked up yet,
+    /// returning the indices of the newly found packages in request order.
+    fn fetch_level(&mut self, state: &mut State, requests: &[(Dependency, Option<String>)])
+        -> Result<Vec<usize>> {
+        let mut names = Vec::new();
+
+        for dependency in requests.iter().map(|request| &request.0) {
+            if state.queried.insert(dependency.name.clone()) {
+                names.push(dependency.name.clone());
+            }
+        }
+
+        for batch in names.chunks(self.batch_size) {
+            let mut packages = (self.fetch)(batch)?;
+            packages.sort_by_key(|package| batch.iter().position(|name| *name == package.name));
+
+            for package in packages {
+                if !state.index.contains_key(&package.name) {
+                    state.add(package);
+                }
+            }
+        }
+
+        Ok(names.iter().filter_map(|name| state.index.get(name).cloned()).collect())
+    }
+
+    /// Looks for a package providing a dependency that no found package
+    /// satisfies, recording whether one was chosen.
+    ///
+    /// A newly found provider is appended to `found`, so that its own
+    /// dependencies are followed.
+    fn provide(&mut self, state: &mut State, dependency: &Dependency, found: &mut Vec<usize>)
+        -> Result<bool> {
+        let existing = provider::candidates(dependency, &state.packages)
+            .first()
+            .map(|candidate| state.index[&candidate.package.name]);
+
+        if let Some(idx) = existing {
+            state.providers.insert(dependency.name.clone(), idx);
+
+            return Ok(true);
+        }
+
+        let providers = match self.providers {
+            Some(ref mut providers) => providers,
+            None => return Ok(false),
+        };
+
+        if !state.searched.insert(dependency.clone()) {
+            return Ok(false);
+        }
+
+        let names = (providers.search)(&dependency.name)?
+            .into_iter()
+            .map(|result| result.name)
+            .filter(|name| !state.index.contains_key(name))
+            .collect::<Vec<_>>();
+        let mut packages = Vec::new();
+
+        for batch in names.chunks(self.batch_size) {
+            packages.extend((self.fetch)(batch)?);
+        }
+
+        let chosen = {
+            let candidates = provider::candidates(dependency, &packages);
+
+            if candidates.is_empty() {
+                return Ok(false);
+            }
+
+            providers.chooser
+                .choose(dependency, &candidates)
+                .and_then(|idx| candidates.get(idx))
+                .map(|candidate| candidate.package.name.clone())
+        };
+        let package = chosen.and_then(|name| {
+            packages.into_iter().find(|package| package.name == name)
+        });
+
+        match package {
+            Some(package) => {
+                state.queried.insert(package.name.clone());
+                let idx = state.add(package);
+                state.providers.insert(dependency.name.clone(), idx);
+                found.push(idx);
+
+                Ok(true)
+            },
+            None => Ok(false),
+        }
+    }
+
+    /// Returns the dependencies of a package that are followed.
+    fn dependencies(&self, package: &InfoResult) -> Vec<Dependency> {
+        let mut dependencies = package.parsed_dependencies();
+
+        if self.make_depends {
+            dependencies.extend(package.parsed_make_depends());
+        }
+
+        if self.check_depends {
+            dependencies.extend(package.parsed_check_depends());
+        }
+
+        dependencies
+    }
+
+    /// Groups the indices of found packages by package base and sorts the
+    /// groups topologically.
+    ///
+    /// Packages that were found but are not needed by any target, such as
+    /// one named after a dependency that a provider satisfies instead, are
+    /// left out.
+    fn build_order(&self, state: &State, targets: &[usize]) -> Result<Vec<Vec<usize>>> {
+        let packages = &state.packages;
+        let mut needed = vec![false; packages.len()];
+        let mut stack = targets.to_vec();
+
+        while let Some(idx) = stack.pop() {
+            if needed[idx] {
+                continue;
+            }
+
+            needed[idx] = true;
+            stack.extend(self.dependencies(&packages[idx])
+                .iter()
+                .filter_map(|dependency| state.resolved(dependency)));
+        }
+
+        let mut bases = Vec::<String>::new();
+        let mut members = HashMap::<String, Vec<usize>>::new();
+
+        for (idx, package) in packages.iter().enumerate().filter(|&(idx, _)| needed[idx]) {
+            if !members.contains_key(&package.package_base) {
+                bases.push(package.package_base.clone());
+            }
+
+            members.entry(package.package_base.clone()).or_default().push(idx);
+        }
+
+        let mut edges = HashMap::<&str, Vec<&str>>::new();
+
+        for (_, package) in packages.iter().enumerate().filter(|&(idx, _)| needed[idx]) {
+            let base = &package.package_base[..];
+
+            for dependency in self.dependencies(package) {
+                let dependency_base = match state.resolved(&dependency) {
+                    Some(idx) => &packages[idx].package_base[..],
+                    None => continue,
+                };
+                let targets = edges.entry(base).or_default();
+
+                if dependency_base != base && !targets.contains(&dependency_base) {
+                    targets.push(dependency_base);
+                }
+            }
+        }
+
+        let mut marks = HashMap::new();
+        let mut path = Vec::new();
+        let mut order = Vec::new();
+
+        for base in &bases {
+            visit(base, &edges, &mut marks, &mut path, &mut order)?;
+        }
+
+        Ok(order.into_iter().map(|base| members[base].clone()).collect())
+    }
+}
+
+/// The packages and dependencies found by a [`Resolver`].
+///
+/// [`Resolver`]: struct.Resolver.html
+#[derive(Clone, Debug, Default)]
+pub struct Resolution {
+    /// The resolved package bases, with each base coming after every base
+    /// it depends on.
+    ///
+    /// Each base only contains its packages that were targets or depended
+    /// upon, in the order they were found, and its requested packages are
+    /// the targets among them.
+    pub build_order: Vec<Base>,
+    /// The targets and dependencies that were neither satisfied nor found on
+    /// the AUR, including those only found in a version not satisfying their
+    /// constraint and those for which no provider was chosen.
+    pub missing: Vec<Missing>,
+    /// The dependencies that were considered satisfied, in the order they
+    /// were first encountered.
+    pub satisfied: Vec<Dependency>,
+}
+
+/// A target or dependency that could not be resolved.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Missing {
+    /// The unresolved target or dependency.
+    pub dependency: Dependency,
+    /// The names of the packages depending on it, which is empty for a
+    /// target.
+    pub required_by: Vec<String>,
+}
+
+/// The progress of a single resolution.
+#[derive(Default)]
+struct State {
+    index: HashMap<String, usize>,
+    missing: Vec<Missing>,
+    packages: Vec<InfoResult>,
+    providers: HashMap<String, usize>,
+    queried: HashSet<String>,
+    satisfied: Vec<Dependency>,
+    satisfied_set: HashSet<Dependency>,
+    searched: HashSet<Dependency>,
+}
+
+impl State {
+    /// Adds a found package, returning its index.
+    fn add(&mut self, package: InfoResult) -> usize {
+        let idx = self.packages.len();
+        self.index.insert(package.name.clone(), idx);
+        self.packages.push(package);
+
+        idx
+    }
+
+    /// Returns the found package satisfying a dependency, either by name or
+    /// as the provider chosen for it.
+    fn satisfier(&self, dependency: &Dependency) -> Option<usize> {
+        let by_name = self.index.get(&dependency.name);
+        let by_provision = self.providers.get(&dependency.name);
+
+        by_name
+            .into_iter()
+            .chain(by_provision)
+            .cloned()
+            .find(|&idx| Candidate::new(&self.packages[idx], dependency).is_some())
+    }
+
+    /// Returns the found package that a dependency is resolved to, which is
+    /// its satisfier or otherwise the package named after it, even in a
+    /// version not satisfying its constraint.
+    fn resolved(&self, dependency: &Dependency) -> Option<usize> {
+        self.satisfier(dependency).or_else(|| self.index.get(&dependency.name).cloned())
+    }
+
+    /// Whether a found package satisfies the dependency.
+    fn provides(&self, dependency: &Dependency) -> bool {
+        self.satisfier(dependency).is_some()
+    }
+
+    /// Records an unresolved dependency and what required it.
+    fn add_missing(&mut self, dependency: Dependency, required_by: Option<String>) {
+        let position = self.missing
+            .iter()
+            .position(|missing| missing.dependency == dependency);
+        let idx = match position {
+            Some(idx) => idx,
+            None => {
+                self.missing.push(Missing {
+                    dependency,
+                    required_by: Vec::new(),
+                });
+
+                self.missing.len() - 1
+            },
+        };
+
+        if let Some(required_by) = required_by {
+            if !self.missing[idx].required_by.contains(&required_by) {
+                self.missing[idx].required_by.push(required_by);
+            }
+        }
+    }
+}
+
+/// The state of a package base in the depth-first search for a build order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mark {
+    Visiting,
+    Done,
+}
+
+/// Appends a package base to the build order after everything it depends on,
+/// failing with the path of any cycle found along the way.
+fn visit<'b>(
+    base: &'b str,
+    edges: &HashMap<&'b str, Vec<&'b str>>,
+    marks: &mut HashMap<&'b str, Mark>,
+    path: &mut Vec<&'b str>,
+    order: &mut Vec<&'b str>,
+) -> Result<()> {
+    match marks.get(base) {
+        Some(&Mark::Done) => return Ok(()),
+        Some(&Mark::Visiting) => {
+            let start = path.iter().position(|&entry| entry == base).unwrap_or(0);
+            let mut cycle = path[start..]
+                .iter()
+                .map(|&entry| entry.to_owned())
+                .collect::<Vec<_>>();
+            cycle.push(base.to_owned());
+
+            return Err(Error::DependencyCycle(cycle));
+        },
+        None => {},
+    }
+
+    marks.insert(base, Mark::Visiting);
+    path.push(base);
+
+    if let Some(targets) = edges.get(base) {
+        for target in targets {
+            visit(target, edges, marks, path, order)?;
+        }
+    }
+
+    path.pop();
+    marks.insert(base, Mark::Done);
+    order.push(base);
+
+    Ok(())
+}
@@ -0,0 +1,216 @@
+//! Safe extraction of package snapshot tarballs.
+//!
+//! The AUR serves the latest state of every package base's git repository as
+//! a gzipped tarball, resolved by [`Endpoint::snapshot_url`]. The bridges
+//! download and extract these through their `aur_download_snapshot` methods,
+//! which use the functions in this module.
+//!
+//! Since tarballs are uploaded by arbitrary users, extraction rejects any
+//! entry that could write or point outside of the destination directory:
+//!
+//! - absolute paths;
+//! - paths containing `..` components;
+//! - device files, FIFOs, and other special files;
+//! - symbolic and hard links whose targets resolve outside the destination,
+//!   or that go up with `..` from anything but a directory that has already
+//!   been extracted, since a later entry could turn it into a link.
+//!
+//! # Examples
+//!
+//! Extract a previously downloaded snapshot:
+//!
+//! ```rust,no_run
+//! use aur::snapshot;
+//!
+//! # fn try_main() -> aur::Result<()> {
+//! snapshot::extract_file("yay.tar.gz", "build")?;
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`Endpoint::snapshot_url`]: ../struct.Endpoint.html#method.snapshot_url
+
+use flate2::read::GzDecoder;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, EntryType};
+use {Error, Result};
+
+/// The maximum number of symbolic links followed while resolving a link
+/// target, matching the limit of Linux.
+const MAX_LINK_DEPTH: usize = 40;
+
+/// Extracts a gzipped snapshot tarball from a reader into a destination
+/// directory, creating the directory if it does not exist.
+///
+/// # Errors
+///
+/// Returns [`Error::Io`] if the tarball could not be decompressed, read, or
+/// unpacked.
+///
+/// Returns [`Error::UnsafeArchiveEntry`] if the tarball contains an entry that
+/// could write or point outside of the destination. Entries preceding it may
+/// have already been extracted.
+///
+/// [`Error::Io`]: ../enum.Error.html#variant.Io
+/// [`Error::UnsafeArchiveEntry`]: ../enum.Error.html#variant.UnsafeArchiveEntry
+pub fn extract<R: Read, P: AsRef<Path>>(reader: R, destination: P) -> Result<()> {
+    unpack(GzDecoder::new(reader), destination)
+}
+
+/// Extracts a gzipped snapshot tarball on disk into a destination directory.
+///
+/// Refer to [`extract`] for the possible errors.
+///
+/// [`extract`]: fn.extract.html
+pub fn extract_file<P: AsRef<Path>, Q: AsRef<Path>>(tarball: P, destination: Q)
+    -> Result<()> {
+    extract(File::open(tarball)?, destination)
+}
+
+/// Extracts an uncompressed tarball from a reader into a destination
+/// directory.
+///
+/// Refer to [`extract`] for the possible errors.
+///
+/// [`extract`]: fn.extract.html
+pub fn unpack<R: Read, P: AsRef<Path>>(reader: R, destination: P) -> Result<()> {
+    let destination = destination.as_ref();
+    fs::create_dir_all(destination)?;
+    let root = destination.canonicalize()?;
+
+    let mut archive = Archive::new(reader);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+
+        match entry.header().entry_type() {
+            // cgit stores the commit of the snapshot in a global header.
+            EntryType::XGlobalHeader => continue,
+            EntryType::Regular | EntryType::Continuous | EntryType::Directory => {},
+            EntryType::Symlink => {
+                let target = entry.link_name()?
+                    .ok_or_else(|| Error::UnsafeArchiveEntry(path.clone()))?
+                    .into_owned();
+                let parent = path.parent().unwrap_or_else(|| Path::new(""));
+                let mut resolved = Vec::new();
+
+                if !resolve(&root, &mut resolved, parent, MAX_LINK_DEPTH)
+                    || !resolve(&root, &mut resolved, &target, MAX_LINK_DEPTH) {
+                    return Err(Error::UnsafeArchiveEntry(path));
+                }
+            },
+            EntryType::Link => {
+                let target = entry.link_name()?
+                    .ok_or_else(|| Error::UnsafeArchiveEntry(path.clone()))?
+                    .into_owned();
+
+                if !resolve(&root, &mut Vec::new(), &target, MAX_LINK_DEPTH) {
+                    return Err(Error::UnsafeArchiveEntry(path));
+                }
+            },
+            _ => return Err(Error::UnsafeArchiveEntry(path)),
+        }
+
+        let parent = path.parent().unwrap_or_else(|| Path::new(""));
+
+        if !is_relative(&path)
+            || !resolve(&root, &mut Vec::new(), parent, MAX_LINK_DEPTH) {
+            return Err(Error::UnsafeArchiveEntry(path));
+        }
+
+        if !entry.unpack_in(&root)? {
+            return Err(Error::UnsafeArchiveEntry(path));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the path that the snapshot tarball of a package base is downloaded
+/// to within a destination directory.
+///
+/// Returns [`Error::InvalidPackageBase`] if the package base name can not be
+/// used as a file name.
+///
+/// [`Error::InvalidPackageBase`]: ../enum.Error.html#variant.InvalidPackageBase
+pub(crate) fn tarball_path(destination: &Path, package_base: &str) -> Result<PathBuf> {
+    check_package_base(package_base)?;
+
+    Ok(destination.join(format!("{}.tar.gz", package_base)))
+}
+
+/// Checks that a package base name can safely be used as a file name within
+/// a directory, as the bridges and the git cache do.
+///
+/// Names are chosen by whoever submits a package, so an empty name, `.`,
+/// `..`, or a name containing a `/` is rejected rather than letting it point
+/// elsewhere.
+pub(crate) fn check_package_base(name: &str) -> Result<()> {
+    if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+        return Err(Error::InvalidPackageBase(name.to_owned()));
+    }
+
+    Ok(())
+}
+
+/// Whether a path is relative and contains no `..` components.
+fn is_relative(path: &Path) -> bool {
+    path.components().all(|component| match component {
+        Component::CurDir | Component::Normal(_) => true,
+        Component::ParentDir | Component::Prefix(_) | Component::RootDir => false,
+    })
+}
+
+/// Resolves a path relative to the directory at `resolved` within `root`,
+/// following any symbolic links already on disk, and returns whether it stays
+/// within `root`.
+///
+/// A `..` component is only followed from a directory that exists, as
+/// whatever is created there later decides where the path leads.
+///
+/// On success, `resolved` holds the components of the resolved path relative
+/// to `root`.
+fn resolve(root: &Path, resolved: &mut Vec<OsString>, path: &Path, depth: usize)
+    -> bool {
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                let is_dir = fs::symlink_metadata(join(root, resolved))
+                    .map(|metadata| metadata.is_dir())
+                    .unwrap_or(false);
+
+                if !is_dir || resolved.pop().is_none() {
+                    return false;
+                }
+            },
+            Component::Normal(name) => {
+                resolved.push(name.to_owned());
+
+                if let Ok(target) = fs::read_link(join(root, resolved)) {
+                    resolved.pop();
+
+                    if depth == 0 || !resolve(root, resolved, &target, depth - 1) {
+                        return false;
+                    }
+                }
+            },
+            Component::Prefix(_) | Component::RootDir => return false,
+        }
+    }
+
+    true
+}
+
+/// Joins resolved components onto the root.
+fn join(root: &Path, resolved: &[OsString]) -> PathBuf {
+    resolved.iter().fold(root.to_owned(), |path, name| path.join(name))
+}
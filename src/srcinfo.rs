@@ -0,0 +1,894 @@
+//! Parsing and writing of `.SRCINFO` files.
+//!
+//! Every package base on the AUR contains a `.SRCINFO` file generated by
+//! `makepkg --printsrcinfo`, describing the package base and each of its split
+//! packages. It carries metadata that the RPC interface does not return, such
+//! as sources, checksums, and architecture-specific dependencies.
+//!
+//! A [`Srcinfo`] holds a [`PackageBase`] and its [`SplitPackage`]s as written
+//! in the file. Split packages only hold the attributes that they override;
+//! [`Srcinfo::resolve`] applies makepkg's inheritance rules to produce the
+//! complete metadata of a package.
+//!
+//! Formatting a [`Srcinfo`] with [`Display`] writes it back out in the format
+//! and key order used by makepkg, so that parsing a generated file and writing
+//! it again gives back the same text.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aur::srcinfo::{self, Srcinfo};
+//!
+//! # fn try_main() -> aur::Result<()> {
+//! let srcinfo = Srcinfo::parse("\
+//! pkgbase = example
+//! \tpkgver = 1.0.0
+//! \tpkgrel = 1
+//! \tarch = x86_64
+//! \tarch = aarch64
+//! \tdepends = glibc
+//! \tdepends_aarch64 = libatomic
+//!
+//! pkgname = example
+//!
+//! pkgname = example-docs
+//! \tarch = any
+//! \tdepends =
+//! ")?;
+//!
+//! let example = srcinfo.resolve("example").unwrap();
+//! assert_eq!(srcinfo::for_arch(&example.depends, "aarch64"), ["glibc", "libatomic"]);
+//!
+//! let docs = srcinfo.resolve("example-docs").unwrap();
+//! assert_eq!(docs.arch, ["any"]);
+//! assert!(docs.depends.is_empty());
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+//! [`PackageBase`]: struct.PackageBase.html
+//! [`SplitPackage`]: struct.SplitPackage.html
+//! [`Srcinfo`]: struct.Srcinfo.html
+//! [`Srcinfo::resolve`]: struct.Srcinfo.html#method.resolve
+
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::result::Result as StdResult;
+use std::str::FromStr;
+use version::Version;
+use {Error, Result};
+
+/// The values of an attribute for a single architecture, such as the values
+/// of `depends_x86_64`, or the values shared by every architecture.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ArchValues {
+    /// The architecture that the values apply to, or `None` if they apply to
+    /// every architecture.
+    pub arch: Option<String>,
+    /// The values of the attribute, in order.
+    ///
+    /// In a [`SplitPackage`], this is empty if the package overrides the
+    /// attribute to have no values.
+    ///
+    /// [`SplitPackage`]: struct.SplitPackage.html
+    pub values: Vec<String>,
+}
+
+impl ArchValues {
+    /// Whether the values apply to the given architecture.
+    pub fn applies_to(&self, arch: &str) -> bool {
+        match self.arch {
+            Some(ref own) => own == arch,
+            None => true,
+        }
+    }
+}
+
+/// Returns the values of an architecture-specific attribute that apply to
+/// the given architecture, with the values shared by every architecture
+/// first.
+pub fn for_arch<'a>(values: &'a [ArchValues], arch: &str) -> Vec<&'a str> {
+    let shared = values.iter().filter(|entry| entry.arch.is_none());
+    let specific = values.iter().filter(|entry| entry.arch.is_some());
+
+    shared
+        .chain(specific)
+        .filter(|entry| entry.applies_to(arch))
+        .flat_map(|entry| entry.values.iter().map(|value| &value[..]))
+        .collect()
+}
+
+/// The `pkgbase` section of a `.SRCINFO` file, holding the attributes shared
+/// by every split package.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PackageBase {
+    /// The architectures that the packages can be built for, from `arch`.
+    pub arch: Vec<String>,
+    /// The BLAKE2 checksums of the sources, from `b2sums`.
+    pub b2_sums: Vec<ArchValues>,
+    /// Files to back up on upgrade, from `backup`.
+    pub backup: Vec<String>,
+    /// The changelog file, from `changelog`.
+    pub changelog: Option<String>,
+    /// Dependencies needed to run the test suite, from `checkdepends`.
+    pub check_depends: Vec<ArchValues>,
+    /// The CRC checksums of the sources, from `cksums`.
+    pub ck_sums: Vec<ArchValues>,
+    /// Conflicting packages, from `conflicts`.
+    pub conflicts: Vec<ArchValues>,
+    /// Runtime dependencies, from `depends`.
+    pub depends: Vec<ArchValues>,
+    /// The description, from `pkgdesc`.
+    pub description: Option<String>,
+    /// The epoch of the version, from `epoch`.
+    pub epoch: Option<String>,
+    /// The groups that the packages belong to, from `groups`.
+    pub groups: Vec<String>,
+    /// The install script, from `install`.
+    pub install: Option<String>,
+    /// The licenses, from `license`.
+    pub license: Vec<String>,
+    /// Dependencies needed to build the packages, from `makedepends`.
+    pub make_depends: Vec<ArchValues>,
+    /// The MD5 checksums of the sources, from `md5sums`.
+    pub md5_sums: Vec<ArchValues>,
+    /// The name of the package base, from `pkgbase`.
+    pub name: String,
+    /// Sources not to extract, from `noextract`.
+    pub no_extract: Vec<String>,
+    /// Optional dependencies, from `optdepends`.
+    pub opt_depends: Vec<ArchValues>,
+    /// Build options, from `options`.
+    pub options: Vec<String>,
+    /// The release number of the version, from `pkgrel`.
+    pub pkgrel: String,
+    /// The upstream version, from `pkgver`.
+    pub pkgver: String,
+    /// Virtual packages provided, from `provides`.
+    pub provides: Vec<ArchValues>,
+    /// Packages replaced, from `replaces`.
+    pub replaces: Vec<ArchValues>,
+    /// The SHA-1 checksums of the sources, from `sha1sums`.
+    pub sha1_sums: Vec<ArchValues>,
+    /// The SHA-224 checksums of the sources, from `sha224sums`.
+    pub sha224_sums: Vec<ArchValues>,
+    /// The SHA-256 checksums of the sources, from `sha256sums`.
+    pub sha256_sums: Vec<ArchValues>,
+    /// The SHA-384 checksums of the sources, from `sha384sums`.
+    pub sha384_sums: Vec<ArchValues>,
+    /// The SHA-512 checksums of the sources, from `sha512sums`.
+    pub sha512_sums: Vec<ArchValues>,
+    /// The sources, from `source`.
+    pub source: Vec<ArchValues>,
+    /// The upstream URL, from `url`.
+    pub url: Option<String>,
+    /// Fingerprints of the PGP keys trusted to sign sources, from
+    /// `validpgpkeys`.
+    pub valid_pgp_keys: Vec<String>,
+}
+
+impl PackageBase {
+    /// Returns the full version of the package base, including the epoch
+    /// and release number.
+    pub fn version(&self) -> Version {
+        full_version(&self.epoch, &self.pkgver, &self.pkgrel)
+    }
+
+    fn field(&mut self, key: &str) -> Option<Field<'_>> {
+        Some(match key {
+            "arch" => Field::Array(&mut self.arch),
+            "b2sums" => Field::Arch(&mut self.b2_sums),
+            "backup" => Field::Array(&mut self.backup),
+            "changelog" => Field::Scalar(&mut self.changelog),
+            "checkdepends" => Field::Arch(&mut self.check_depends),
+            "cksums" => Field::Arch(&mut self.ck_sums),
+            "conflicts" => Field::Arch(&mut self.conflicts),
+            "depends" => Field::Arch(&mut self.depends),
+            "epoch" => Field::Scalar(&mut self.epoch),
+            "groups" => Field::Array(&mut self.groups),
+            "install" => Field::Scalar(&mut self.install),
+            "license" => Field::Array(&mut self.license),
+            "makedepends" => Field::Arch(&mut self.make_depends),
+            "md5sums" => Field::Arch(&mut self.md5_sums),
+            "noextract" => Field::Array(&mut self.no_extract),
+            "optdepends" => Field::Arch(&mut self.opt_depends),
+            "options" => Field::Array(&mut self.options),
+            "pkgdesc" => Field::Scalar(&mut self.description),
+            "pkgrel" => Field::Required(&mut self.pkgrel),
+            "pkgver" => Field::Required(&mut self.pkgver),
+            "provides" => Field::Arch(&mut self.provides),
+            "replaces" => Field::Arch(&mut self.replaces),
+            "sha1sums" => Field::Arch(&mut self.sha1_sums),
+            "sha224sums" => Field::Arch(&mut self.sha224_sums),
+            "sha256sums" => Field::Arch(&mut self.sha256_sums),
+            "sha384sums" => Field::Arch(&mut self.sha384_sums),
+            "sha512sums" => Field::Arch(&mut self.sha512_sums),
+            "source" => Field::Arch(&mut self.source),
+            "url" => Field::Scalar(&mut self.url),
+            "validpgpkeys" => Field::Array(&mut self.valid_pgp_keys),
+            _ => return None,
+        })
+    }
+}
+
+/// A `pkgname` section of a `.SRCINFO` file, holding the attributes that a
+/// split package overrides.
+///
+/// Attributes that are `None` or absent are inherited from the
+/// [`PackageBase`]. An attribute overridden to be empty is `Some` empty
+/// string or list, or an [`ArchValues`] without values.
+///
+/// [`ArchValues`]: struct.ArchValues.html
+/// [`PackageBase`]: struct.PackageBase.html
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SplitPackage {
+    /// The overridden architectures, from `arch`.
+    pub arch: Option<Vec<String>>,
+    /// The overridden files to back up on upgrade, from `backup`.
+    pub backup: Option<Vec<String>>,
+    /// The overridden changelog file, from `changelog`.
+    pub changelog: Option<String>,
+    /// The overridden test suite dependencies, from `checkdepends`.
+    pub check_depends: Vec<ArchValues>,
+    /// The overridden conflicting packages, from `conflicts`.
+    pub conflicts: Vec<ArchValues>,
+    /// The overridden runtime dependencies, from `depends`.
+    pub depends: Vec<ArchValues>,
+    /// The overridden description, from `pkgdesc`.
+    pub description: Option<String>,
+    /// The overridden groups, from `groups`.
+    pub groups: Option<Vec<String>>,
+    /// The overridden install script, from `install`.
+    pub install: Option<String>,
+    /// The overridden licenses, from `license`.
+    pub license: Option<Vec<String>>,
+    /// The name of the package, from `pkgname`.
+    pub name: String,
+    /// The overridden optional dependencies, from `optdepends`.
+    pub opt_depends: Vec<ArchValues>,
+    /// The overridden build options, from `options`.
+    pub options: Option<Vec<String>>,
+    /// The overridden virtual packages provided, from `provides`.
+    pub provides: Vec<ArchValues>,
+    /// The overridden packages replaced, from `replaces`.
+    pub replaces: Vec<ArchValues>,
+    /// The overridden upstream URL, from `url`.
+    pub url: Option<String>,
+}
+
+impl SplitPackage {
+    fn field(&mut self, key: &str) -> Option<Field<'_>> {
+        Some(match key {
+            "arch" => Field::Override(&mut self.arch),
+            "backup" => Field::Override(&mut self.backup),
+            "changelog" => Field::Scalar(&mut self.changelog),
+            "checkdepends" => Field::Arch(&mut self.check_depends),
+            "conflicts" => Field::Arch(&mut self.conflicts),
+            "depends" => Field::Arch(&mut self.depends),
+            "groups" => Field::Override(&mut self.groups),
+            "install" => Field::Scalar(&mut self.install),
+            "license" => Field::Override(&mut self.license),
+            "optdepends" => Field::Arch(&mut self.opt_depends),
+            "options" => Field::Override(&mut self.options),
+            "pkgdesc" => Field::Scalar(&mut self.description),
+            "provides" => Field::Arch(&mut self.provides),
+            "replaces" => Field::Arch(&mut self.replaces),
+            "url" => Field::Scalar(&mut self.url),
+            _ => return None,
+        })
+    }
+}
+
+/// The complete metadata of a split package, after applying the inheritance
+/// rules to its [`PackageBase`].
+///
+/// [`PackageBase`]: struct.PackageBase.html
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ResolvedPackage {
+    /// The architectures that the package can be built for.
+    pub arch: Vec<String>,
+    /// Files to back up on upgrade.
+    pub backup: Vec<String>,
+    /// The changelog file.
+    pub changelog: Option<String>,
+    /// Dependencies needed to run the test suite.
+    pub check_depends: Vec<ArchValues>,
+    /// Conflicting packages.
+    pub conflicts: Vec<ArchValues>,
+    /// Runtime dependencies.
+    pub depends: Vec<ArchValues>,
+    /// The description.
+    pub description: Option<String>,
+    /// The epoch of the version.
+    pub epoch: Option<String>,
+    /// The groups that the package belongs to.
+    pub groups: Vec<String>,
+    /// The install script.
+    pub install: Option<String>,
+    /// The licenses.
+    pub license: Vec<String>,
+    /// Dependencies needed to build the package base.
+    pub make_depends: Vec<ArchValues>,
+    /// The name of the package.
+    pub name: String,
+    /// Optional dependencies.
+    pub opt_depends: Vec<ArchValues>,
+    /// Build options.
+    pub options: Vec<String>,
+    /// The name of the package base.
+    pub package_base: String,
+    /// The release number of the version.
+    pub pkgrel: String,
+    /// The upstream version.
+    pub pkgver: String,
+    /// Virtual packages provided.
+    pub provides: Vec<ArchValues>,
+    /// Packages replaced.
+    pub replaces: Vec<ArchValues>,
+    /// The upstream URL.
+    pub url: Option<String>,
+}
+
+impl ResolvedPackage {
+    /// Returns the full version of the package, including the epoch and
+    /// release number.
+    pub fn version(&self) -> Version {
+        full_version(&self.epoch, &self.pkgver, &self.pkgrel)
+    }
+}
+
+/// A parsed `.SRCINFO` file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Srcinfo {
+    /// The package base.
+    pub base: PackageBase,
+    /// The split packages, in the order they were written.
+    pub packages: Vec<SplitPackage>,
+}
+
+impl Srcinfo {
+    /// Parses the contents of a `.SRCINFO` file.
+    ///
+    /// Blank lines and comments are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Srcinfo`] with the number of the offending line if
+    /// the file is malformed, such as for an unknown key or a missing
+    /// `pkgver`.
+    ///
+    /// [`Error::Srcinfo`]: ../enum.Error.html#variant.Srcinfo
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut base = None;
+        let mut base_line = 0;
+        let mut packages = Vec::<SplitPackage>::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |kind| Error::Srcinfo(ParseError {
+                kind,
+                line: number,
+            });
+
+            let (key, value) = match line.find('=') {
+                Some(pos) => (line[..pos].trim(), line[pos + 1..].trim()),
+                None => return Err(error(ParseErrorKind::MissingEquals)),
+            };
+
+            match key {
+                "pkgbase" => {
+                    if base.is_some() {
+                        return Err(error(ParseErrorKind::DuplicateKey(key.to_owned())));
+                    }
+
+                    if value.is_empty() {
+                        return Err(error(ParseErrorKind::EmptyValue(key.to_owned())));
+                    }
+
+                    base = Some(PackageBase {
+                        name: value.to_owned(),
+                        ..PackageBase::default()
+                    });
+                    base_line = number;
+                },
+                "pkgname" => {
+                    if base.is_none() {
+                        return Err(error(ParseErrorKind::MissingKey("pkgbase")));
+                    }
+
+                    if value.is_empty() {
+                        return Err(error(ParseErrorKind::EmptyValue(key.to_owned())));
+                    }
+
+                    if packages.iter().any(|package| package.name == value) {
+                        return Err(error(ParseErrorKind::DuplicatePackage(value.to_owned())));
+                    }
+
+                    packages.push(SplitPackage {
+                        name: value.to_owned(),
+                        ..SplitPackage::default()
+                    });
+                },
+                _ => {
+                    let (name, arch) = match key.find('_') {
+                        Some(pos) => (&key[..pos], Some(&key[pos + 1..])),
+                        None => (key, None),
+                    };
+
+                    let field = match (packages.last_mut(), base.as_mut()) {
+                        (Some(package), _) => package.field(name),
+                        (None, Some(base)) => base.field(name),
+                        (None, None) => {
+                            return Err(error(ParseErrorKind::MissingKey("pkgbase")));
+                        },
+                    };
+
+                    let field = match field {
+                        Some(field) => field,
+                        None => return Err(error(ParseErrorKind::UnknownKey(key.to_owned()))),
+                    };
+
+                    field.set(key, arch, value).map_err(error)?;
+                },
+            }
+        }
+
+        let base = match base {
+            Some(base) => base,
+            None => return Err(Error::Srcinfo(ParseError {
+                kind: ParseErrorKind::MissingKey("pkgbase"),
+                line: 1,
+            })),
+        };
+
+        let missing = if base.pkgver.is_empty() {
+            Some("pkgver")
+        } else if base.pkgrel.is_empty() {
+            Some("pkgrel")
+        } else if packages.is_empty() {
+            Some("pkgname")
+        } else {
+            None
+        };
+
+        if let Some(key) = missing {
+            return Err(Error::Srcinfo(ParseError {
+                kind: ParseErrorKind::MissingKey(key),
+                line: base_line,
+            }));
+        }
+
+        Ok(Srcinfo {
+            base,
+            packages,
+        })
+    }
+
+    /// Returns the names of the split packages, in order.
+    pub fn names(&self) -> Vec<&str> {
+        self.packages.iter().map(|package| &package.name[..]).collect()
+    }
+
+    /// Returns the split package with the given name, holding only the
+    /// attributes that it overrides.
+    pub fn package(&self, name: &str) -> Option<&SplitPackage> {
+        self.packages.iter().find(|package| package.name == name)
+    }
+
+    /// Returns the complete metadata of the split package with the given
+    /// name, inheriting every attribute it does not override from the
+    /// package base.
+    ///
+    /// Architecture-specific attributes are inherited per architecture, so a
+    /// package overriding `depends_x86_64` still inherits `depends`. Values
+    /// for architectures that the package is not built for and attributes
+    /// overridden to be empty are removed.
+    pub fn resolve(&self, name: &str) -> Option<ResolvedPackage> {
+        let package = self.package(name)?;
+        let base = &self.base;
+        let arch = override_array(&package.arch, &base.arch);
+        let inherit = |package, base| override_arch(package, base, &arch);
+
+        Some(ResolvedPackage {
+            backup: override_array(&package.backup, &base.backup),
+            changelog: override_scalar(&package.changelog, &base.changelog),
+            check_depends: inherit(&package.check_depends, &base.check_depends),
+            conflicts: inherit(&package.conflicts, &base.conflicts),
+            depends: inherit(&package.depends, &base.depends),
+            description: override_scalar(&package.description, &base.description),
+            epoch: base.epoch.clone(),
+            groups: override_array(&package.groups, &base.groups),
+            install: override_scalar(&package.install, &base.install),
+            license: override_array(&package.license, &base.license),
+            make_depends: inherit(&[], &base.make_depends),
+            name: package.name.clone(),
+            opt_depends: inherit(&package.opt_depends, &base.opt_depends),
+            options: override_array(&package.options, &base.options),
+            package_base: base.name.clone(),
+            pkgrel: base.pkgrel.clone(),
+            pkgver: base.pkgver.clone(),
+            provides: inherit(&package.provides, &base.provides),
+            replaces: inherit(&package.replaces, &base.replaces),
+            url: override_scalar(&package.url, &base.url),
+            arch,
+        })
+    }
+}
+
+impl Display for Srcinfo {
+    /// Writes the `.SRCINFO` text of the package base and its split packages,
+    /// in the format and key order used by `makepkg --printsrcinfo`.
+    ///
+    /// Architecture-specific values are written for each architecture in
+    /// the section's `arch`, followed by any other architectures they are
+    /// given for.
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let base = &self.base;
+
+        writeln!(f, "pkgbase = {}", base.name)?;
+        write_scalar(f, "pkgdesc", &base.description)?;
+        write_values(f, "pkgver", &[&base.pkgver[..]])?;
+        write_values(f, "pkgrel", &[&base.pkgrel[..]])?;
+        write_scalar(f, "epoch", &base.epoch)?;
+        write_scalar(f, "url", &base.url)?;
+        write_scalar(f, "install", &base.install)?;
+        write_scalar(f, "changelog", &base.changelog)?;
+        write_array(f, "arch", &base.arch)?;
+        write_array(f, "groups", &base.groups)?;
+        write_array(f, "license", &base.license)?;
+        write_arch(f, "checkdepends", &base.check_depends, None)?;
+        write_arch(f, "makedepends", &base.make_depends, None)?;
+        write_arch(f, "depends", &base.depends, None)?;
+        write_arch(f, "optdepends", &base.opt_depends, None)?;
+        write_arch(f, "provides", &base.provides, None)?;
+        write_arch(f, "conflicts", &base.conflicts, None)?;
+        write_arch(f, "replaces", &base.replaces, None)?;
+        write_array(f, "noextract", &base.no_extract)?;
+        write_array(f, "options", &base.options)?;
+        write_array(f, "backup", &base.backup)?;
+        write_arch(f, "source", &base.source, None)?;
+        write_array(f, "validpgpkeys", &base.valid_pgp_keys)?;
+
+        let sums = [
+            ("cksums", &base.ck_sums[..]),
+            ("md5sums", &base.md5_sums[..]),
+            ("sha1sums", &base.sha1_sums[..]),
+            ("sha224sums", &base.sha224_sums[..]),
+            ("sha256sums", &base.sha256_sums[..]),
+            ("sha384sums", &base.sha384_sums[..]),
+            ("sha512sums", &base.sha512_sums[..]),
+            ("b2sums", &base.b2_sums[..]),
+        ];
+
+        for &(key, entries) in &sums {
+            write_arch(f, key, entries, None)?;
+        }
+
+        let mut arch_specific = vec![
+            ("source", &base.source[..]),
+            ("provides", &base.provides[..]),
+            ("conflicts", &base.conflicts[..]),
+            ("depends", &base.depends[..]),
+            ("replaces", &base.replaces[..]),
+            ("optdepends", &base.opt_depends[..]),
+            ("makedepends", &base.make_depends[..]),
+            ("checkdepends", &base.check_depends[..]),
+        ];
+        arch_specific.extend(&sums);
+
+        write_arch_specific(f, &base.arch, &arch_specific)?;
+        writeln!(f)?;
+
+        for package in &self.packages {
+            writeln!(f, "pkgname = {}", package.name)?;
+            write_scalar(f, "pkgdesc", &package.description)?;
+            write_scalar(f, "url", &package.url)?;
+            write_scalar(f, "install", &package.install)?;
+            write_scalar(f, "changelog", &package.changelog)?;
+            write_override(f, "arch", &package.arch)?;
+            write_override(f, "groups", &package.groups)?;
+            write_override(f, "license", &package.license)?;
+            write_arch(f, "checkdepends", &package.check_depends, None)?;
+            write_arch(f, "depends", &package.depends, None)?;
+            write_arch(f, "optdepends", &package.opt_depends, None)?;
+            write_arch(f, "provides", &package.provides, None)?;
+            write_arch(f, "conflicts", &package.conflicts, None)?;
+            write_arch(f, "replaces", &package.replaces, None)?;
+            write_override(f, "options", &package.options)?;
+            write_override(f, "backup", &package.backup)?;
+
+            let arches = package.arch.as_ref().unwrap_or(&base.arch);
+
+            write_arch_specific(f, arches, &[
+                ("provides", &package.provides[..]),
+                ("conflicts", &package.conflicts[..]),
+                ("depends", &package.depends[..]),
+                ("replaces", &package.replaces[..]),
+                ("optdepends", &package.opt_depends[..]),
+                ("checkdepends", &package.check_depends[..]),
+            ])?;
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Srcinfo {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        Srcinfo::parse(text)
+    }
+}
+
+/// An error parsing a `.SRCINFO` file, with the number of the line it
+/// occurred on.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ParseError {
+    /// The kind of error.
+    pub kind: ParseErrorKind,
+    /// The number of the line the error occurred on, starting at 1.
+    pub line: usize,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "line {}: ", self.line)?;
+
+        match self.kind {
+            ParseErrorKind::DuplicateKey(ref key) => write!(f, "duplicate key {}", key),
+            ParseErrorKind::DuplicatePackage(ref name) => {
+                write!(f, "duplicate package {}", name)
+            },
+            ParseErrorKind::EmptyValue(ref key) => write!(f, "empty value for {}", key),
+            ParseErrorKind::MissingEquals => f.write_str("missing '='"),
+            ParseErrorKind::MissingKey(key) => write!(f, "missing key {}", key),
+            ParseErrorKind::UnknownKey(ref key) => write!(f, "unknown key {}", key),
+        }
+    }
+}
+
+impl StdError for ParseError {
+    fn description(&self) -> &str {
+        match self.kind {
+            ParseErrorKind::DuplicateKey(_) => "Duplicate key",
+            ParseErrorKind::DuplicatePackage(_) => "Duplicate package",
+            ParseErrorKind::EmptyValue(_) => "Empty value",
+            ParseErrorKind::MissingEquals => "Missing '='",
+            ParseErrorKind::MissingKey(_) => "Missing key",
+            ParseErrorKind::UnknownKey(_) => "Unknown key",
+        }
+    }
+}
+
+/// The kind of a [`ParseError`].
+///
+/// [`ParseError`]: struct.ParseError.html
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ParseErrorKind {
+    /// A key holding a single value was given more than once.
+    DuplicateKey(String),
+    /// A split package was given more than once.
+    DuplicatePackage(String),
+    /// A key requiring a value was given an empty one.
+    EmptyValue(String),
+    /// A line is neither blank, a comment, nor a `key = value` pair.
+    MissingEquals,
+    /// A required key was not given, such as `pkgver`, or was not given
+    /// first, in the case of `pkgbase`.
+    MissingKey(&'static str),
+    /// A key is unknown, not allowed in its section, or not allowed to be
+    /// suffixed with an architecture.
+    UnknownKey(String),
+}
+
+/// A mutable reference to the attribute of a section that a key sets.
+enum Field<'a> {
+    /// An attribute that may be suffixed with an architecture.
+    Arch(&'a mut Vec<ArchValues>),
+    /// A list attribute of the package base.
+    Array(&'a mut Vec<String>),
+    /// A list attribute that a split package may override.
+    Override(&'a mut Option<Vec<String>>),
+    /// A single-valued attribute that must be given.
+    Required(&'a mut String),
+    /// An optional single-valued attribute.
+    Scalar(&'a mut Option<String>),
+}
+
+impl<'a> Field<'a> {
+    fn set(self, key: &str, arch: Option<&str>, value: &str)
+        -> StdResult<(), ParseErrorKind> {
+        let value = if value.is_empty() {
+            None
+        } else {
+            Some(value.to_owned())
+        };
+
+        match self {
+            Field::Arch(entries) => {
+                let pos = entries
+                    .iter()
+                    .position(|entry| entry.arch.as_ref().map(|arch| &arch[..]) == arch);
+
+                let entry = match pos {
+                    Some(pos) => &mut entries[pos],
+                    None => {
+                        entries.push(ArchValues {
+                            arch: arch.map(ToOwned::to_owned),
+                            values: Vec::new(),
+                        });
+
+                        entries.last_mut().unwrap()
+                    },
+                };
+
+                entry.values.extend(value);
+
+                return Ok(());
+            },
+            _ if arch.is_some() => {
+                return Err(ParseErrorKind::UnknownKey(key.to_owned()));
+            },
+            Field::Array(values) => values.extend(value),
+            Field::Override(values) => {
+                values.get_or_insert_with(Vec::new).extend(value);
+            },
+            Field::Required(current) => {
+                if !current.is_empty() {
+                    return Err(ParseErrorKind::DuplicateKey(key.to_owned()));
+                }
+
+                match value {
+                    Some(value) => *current = value,
+                    None => return Err(ParseErrorKind::EmptyValue(key.to_owned())),
+                }
+            },
+            Field::Scalar(current) => {
+                if current.is_some() {
+                    return Err(ParseErrorKind::DuplicateKey(key.to_owned()));
+                }
+
+                *current = Some(value.unwrap_or_default());
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the values of an attribute for the given architecture, or for
+/// every architecture if `None`.
+fn write_arch(f: &mut Formatter, key: &str, entries: &[ArchValues], arch: Option<&str>)
+    -> FmtResult {
+    let matching = entries
+        .iter()
+        .filter(|entry| entry.arch.as_ref().map(|arch| &arch[..]) == arch);
+
+    for entry in matching {
+        match arch {
+            Some(arch) => write_values(f, &format!("{}_{}", key, arch), &entry.values)?,
+            None => write_values(f, key, &entry.values)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the architecture-specific values of the attributes, for each
+/// architecture in turn.
+///
+/// makepkg has no architecture-specific values for `any`.
+fn write_arch_specific(
+    f: &mut Formatter,
+    arches: &[String],
+    attributes: &[(&str, &[ArchValues])],
+) -> FmtResult {
+    let mut all = arches
+        .iter()
+        .filter(|arch| *arch != "any")
+        .map(|arch| &arch[..])
+        .collect::<Vec<_>>();
+
+    for &(_, entries) in attributes {
+        for arch in entries.iter().filter_map(|entry| entry.arch.as_ref()) {
+            if !all.contains(&&arch[..]) {
+                all.push(arch);
+            }
+        }
+    }
+
+    for arch in all {
+        for &(key, entries) in attributes {
+            write_arch(f, key, entries, Some(arch))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_array(f: &mut Formatter, key: &str, values: &[String]) -> FmtResult {
+    if values.is_empty() {
+        Ok(())
+    } else {
+        write_values(f, key, values)
+    }
+}
+
+fn write_override(f: &mut Formatter, key: &str, values: &Option<Vec<String>>) -> FmtResult {
+    match *values {
+        Some(ref values) => write_values(f, key, values),
+        None => Ok(()),
+    }
+}
+
+fn write_scalar(f: &mut Formatter, key: &str, value: &Option<String>) -> FmtResult {
+    match *value {
+        Some(ref value) => write_values(f, key, &[value]),
+        None => Ok(()),
+    }
+}
+
+/// Writes a line for each value, or a single line without a value if there
+/// are none, which is how makepkg writes an attribute overridden to be empty.
+fn write_values<S: AsRef<str>>(f: &mut Formatter, key: &str, values: &[S]) -> FmtResult {
+    if values.is_empty() {
+        return writeln!(f, "\t{} = ", key);
+    }
+
+    for value in values {
+        writeln!(f, "\t{} = {}", key, value.as_ref())?;
+    }
+
+    Ok(())
+}
+
+fn full_version(epoch: &Option<String>, pkgver: &str, pkgrel: &str) -> Version {
+    match *epoch {
+        Some(ref epoch) if !epoch.is_empty() => {
+            Version::parse(&format!("{}:{}-{}", epoch, pkgver, pkgrel))
+        },
+        _ => Version::parse(&format!("{}-{}", pkgver, pkgrel)),
+    }
+}
+
+fn override_arch(package: &[ArchValues], base: &[ArchValues], arches: &[String])
+    -> Vec<ArchValues> {
+    base.iter()
+        .filter(|entry| !package.iter().any(|over| over.arch == entry.arch))
+        .chain(package)
+        .filter(|entry| !entry.values.is_empty())
+        .filter(|entry| match entry.arch {
+            Some(ref arch) => arches.contains(arch),
+            None => true,
+        })
+        .cloned()
+        .collect()
+}
+
+fn override_array(package: &Option<Vec<String>>, base: &[String]) -> Vec<String> {
+    match *package {
+        Some(ref values) => values.clone(),
+        None => base.to_vec(),
+    }
+}
+
+fn override_scalar(package: &Option<String>, base: &Option<String>) -> Option<String> {
+    match *package {
+        Some(ref value) if value.is_empty() => None,
+        Some(ref value) => Some(value.clone()),
+        None => base.clone(),
+    }
+}
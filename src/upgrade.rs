@@ -0,0 +1,265 @@
+//! Checking installed AUR packages for upgrades.
+//!
+//! An [`UpgradeChecker`] looks up the installed packages on the AUR in
+//! batches and compares their versions the way pacman does, reporting which
+//! have newer versions on the AUR, which are newer locally, and which no
+//! longer exist on the AUR. Packages that no longer exist can be checked for
+//! an AUR package that `replaces` them, which is how renames are detected.
+//!
+//! # Examples
+//!
+//! Print the available upgrades of two installed packages:
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "reqwest")]
+//! extern crate aur;
+//! # #[cfg(feature = "reqwest")]
+//! extern crate reqwest;
+//!
+//! # #[cfg(feature = "reqwest")]
+//! # fn try_main() -> Result<(), Box<::std::error::Error>> {
+//! #
+//! use aur::bridge::reqwest::AurRequester;
+//! use aur::model::SearchBy;
+//! use aur::upgrade::UpgradeChecker;
+//! use reqwest::Client;
+//!
+//! let client = Client::new();
+//!
+//! let report = UpgradeChecker::new(|names| Ok(client.aur_info(names)?.results))
+//!     .replacements(|name| Ok(client.aur_search_by(name, SearchBy::Replaces)?.results))
+//!     .ignore(&["linux-custom"])
+//!     .check(&[("yay", "12.3.5-1"), ("linux-custom", "6.1-1")])?;
+//!
+//! for upgrade in &report.upgrades {
+//!     println!("{} {} -> {}", upgrade.name, upgrade.local_version, upgrade.package.version);
+//! }
+//! #     Ok(())
+//! # }
+//! #
+//! # #[cfg(not(feature = "reqwest"))]
+//! # fn try_main() -> Result<(), Box<::std::error::Error>> { Ok(()) }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`UpgradeChecker`]: struct.UpgradeChecker.html
+
+use dependency::Dependency;
+use model::{InfoResult, SearchResult};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use version::Version;
+use Result;
+
+/// The default maximum number of packages requested at once.
+const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// A function looking up the information of a batch of packages by name.
+type Fetch<'a> = Box<FnMut(&[String]) -> Result<Vec<InfoResult>> + 'a>;
+
+/// A function searching for the packages replacing a name.
+type ReplacementSearch<'a> = Box<FnMut(&str) -> Result<Vec<SearchResult>> + 'a>;
+
+/// Checks installed packages for upgrades on the AUR.
+pub struct UpgradeChecker<'a> {
+    batch_size: usize,
+    fetch: Fetch<'a>,
+    ignore: HashSet<String>,
+    replacements: Option<ReplacementSearch<'a>>,
+}
+
+impl<'a> UpgradeChecker<'a> {
+    /// Creates a checker from a function looking up the information of a
+    /// batch of packages by name, such as with a bridge's `aur_info`.
+    ///
+    /// Packages that do not exist should be left out of the looked up
+    /// information rather than returning an error.
+    pub fn new<F>(fetch: F) -> Self
+        where F: FnMut(&[String]) -> Result<Vec<InfoResult>> + 'a {
+        UpgradeChecker {
+            batch_size: DEFAULT_BATCH_SIZE,
+            fetch: Box::new(fetch),
+            ignore: HashSet::new(),
+            replacements: None,
+        }
+    }
+
+    /// Sets the maximum number of packages looked up at once, defaulting to
+    /// 100.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the batch size is zero.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch size must be positive");
+        self.batch_size = batch_size;
+
+        self
+    }
+
+    /// Adds the names of installed packages that are not checked, like
+    /// pacman's `IgnorePkg`.
+    pub fn ignore<I, S>(mut self, names: I) -> Self
+        where I: IntoIterator<Item = S>,
+              S: AsRef<str> {
+        self.ignore.extend(names.into_iter().map(|name| name.as_ref().to_owned()));
+
+        self
+    }
+
+    /// Checks installed packages that no longer exist on the AUR for a
+    /// package replacing them, which is not done by default.
+    ///
+    /// The search function looks up the packages replacing a name, such as
+    /// with a bridge's `aur_search_by` and [`SearchBy::Replaces`]. The
+    /// information of the found packages is then looked up to check the
+    /// version constraints of their `replaces`.
+    ///
+    /// [`SearchBy::Replaces`]: ../model/enum.SearchBy.html#variant.Replaces
+    pub fn replacements<S>(mut self, search: S) -> Self
+        where S: FnMut(&str) -> Result<Vec<SearchResult>> + 'a {
+        self.replacements = Some(Box::new(search));
+
+        self
+    }
+
+    /// Checks installed packages, given as pairs of their names and
+    /// versions.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned while looking up or searching for
+    /// packages.
+    pub fn check<N, V>(&mut self, installed: &[(N, V)]) -> Result<Report>
+        where N: AsRef<str>,
+              V: AsRef<str> {
+        let mut report = Report::default();
+        let mut checked = Vec::new();
+
+        for pair in installed {
+            let (name, version) = (pair.0.as_ref(), pair.1.as_ref());
+
+            if self.ignore.contains(name) {
+                report.ignored.push(name.to_owned());
+            } else {
+                checked.push((name, version));
+            }
+        }
+
+        let names = checked.iter().map(|&(name, _)| name.to_owned()).collect::<Vec<_>>();
+        let found = self.fetch_all(&names)?
+            .into_iter()
+            .map(|package| (package.name.clone(), package))
+            .collect::<HashMap<_, _>>();
+
+        for (name, version) in checked {
+            let package = match found.get(name) {
+                Some(package) => package.clone(),
+                None => {
+                    match self.replacement(name, version)? {
+                        Some(package) => {
+                            report.replaced.push(Upgrade::new(name, version, package))
+                        },
+                        None => report.missing.push(name.to_owned()),
+                    }
+
+                    continue;
+                },
+            };
+
+            match Version::parse(version).vercmp(&Version::parse(&package.version)) {
+                Ordering::Less => report.upgrades.push(Upgrade::new(name, version, package)),
+                Ordering::Equal => report.up_to_date.push(name.to_owned()),
+                Ordering::Greater => {
+                    report.local_newer.push(Upgrade::new(name, version, package))
+                },
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Looks up packages in batches.
+    fn fetch_all(&mut self, names: &[String]) -> Result<Vec<InfoResult>> {
+        let mut packages = Vec::new();
+
+        for batch in names.chunks(self.batch_size) {
+            packages.extend((self.fetch)(batch)?);
+        }
+
+        Ok(packages)
+    }
+
+    /// Finds the most voted package replacing an installed package, if
+    /// replacements are searched for.
+    fn replacement(&mut self, name: &str, version: &str) -> Result<Option<InfoResult>> {
+        let names = match self.replacements {
+            Some(ref mut search) => {
+                search(name)?.into_iter().map(|result| result.name).collect::<Vec<_>>()
+            },
+            None => return Ok(None),
+        };
+        let version = Version::parse(version);
+
+        let mut packages = self.fetch_all(&names)?
+            .into_iter()
+            .filter(|package| {
+                package.replaces.iter().any(|replaced| {
+                    Dependency::parse(replaced).is_satisfied_by(name, &version)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        packages.sort_by(|a, b| b.num_votes.cmp(&a.num_votes).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(packages.into_iter().next())
+    }
+}
+
+/// An installed package along with its counterpart on the AUR.
+#[derive(Clone, Debug)]
+pub struct Upgrade {
+    /// The installed version of the package.
+    pub local_version: String,
+    /// The name of the installed package.
+    pub name: String,
+    /// The package on the AUR, which is named differently when it replaces
+    /// the installed package.
+    pub package: InfoResult,
+}
+
+impl Upgrade {
+    fn new(name: &str, local_version: &str, package: InfoResult) -> Self {
+        Upgrade {
+            local_version: local_version.to_owned(),
+            name: name.to_owned(),
+            package,
+        }
+    }
+}
+
+/// The result of checking installed packages for upgrades.
+///
+/// Every installed package appears in exactly one of the lists, in the
+/// order the packages were given.
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    /// The names of the packages that were ignored.
+    pub ignored: Vec<String>,
+    /// The packages whose installed version is newer than on the AUR.
+    pub local_newer: Vec<Upgrade>,
+    /// The names of the packages that do not exist on the AUR and are not
+    /// replaced by another package.
+    pub missing: Vec<String>,
+    /// The packages that do not exist on the AUR but are replaced by another
+    /// package, such as after a rename.
+    pub replaced: Vec<Upgrade>,
+    /// The names of the packages whose installed version is the same as on
+    /// the AUR.
+    pub up_to_date: Vec<String>,
+    /// The packages with a newer version on the AUR.
+    pub upgrades: Vec<Upgrade>,
+}
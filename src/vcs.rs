@@ -0,0 +1,279 @@
+//! Detection of packages built from version control.
+//!
+//! A VCS package, such as `yay-git`, builds whatever its upstream repository
+//! holds at the time, so its version only changes when it is rebuilt and a
+//! plain version comparison always considers it up to date. These packages
+//! are detected by the suffix of their name and by the sources of their
+//! `.SRCINFO`, and the latest upstream commit of a git source can be looked
+//! up to tell whether a rebuild is needed.
+//!
+//! Looking up upstream commits shells out to the `git` executable, which
+//! must be on the `PATH`.
+//!
+//! # Examples
+//!
+//! Print whether the git sources of a package have moved on since the
+//! commits it was last built from:
+//!
+//! ```rust,no_run
+//! use aur::srcinfo::Srcinfo;
+//! use aur::vcs;
+//!
+//! fn print_changes(srcinfo: &Srcinfo, built: &[&str]) -> aur::Result<()> {
+//!     for (source, built) in vcs::sources(srcinfo).iter().zip(built) {
+//!         if let Some(commit) = source.upstream_commit()? {
+//!             if commit != *built {
+//!                 println!("{} moved to {}", source.url, commit);
+//!             }
+//!         }
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use git;
+use srcinfo::Srcinfo;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::path::Path;
+use {Error, Result};
+
+/// A version control system that packages are built from.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Vcs {
+    /// Bazaar.
+    Bzr,
+    /// CVS.
+    Cvs,
+    /// Darcs.
+    Darcs,
+    /// Fossil.
+    Fossil,
+    /// Git.
+    Git,
+    /// Mercurial.
+    Hg,
+    /// Subversion.
+    Svn,
+}
+
+impl Vcs {
+    /// Returns the version control system named by the suffix of a package
+    /// name, such as [`Vcs::Git`] for `yay-git`.
+    ///
+    /// [`Vcs::Git`]: enum.Vcs.html#variant.Git
+    pub fn from_name(name: &str) -> Option<Vcs> {
+        const ALL: &[Vcs] = &[
+            Vcs::Bzr,
+            Vcs::Cvs,
+            Vcs::Darcs,
+            Vcs::Fossil,
+            Vcs::Git,
+            Vcs::Hg,
+            Vcs::Svn,
+        ];
+
+        ALL.iter().cloned().find(|vcs| name.ends_with(&format!("-{}", vcs)))
+    }
+
+    /// Returns the name of the version control system, as used in package
+    /// name suffixes and source URLs.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Vcs::Bzr => "bzr",
+            Vcs::Cvs => "cvs",
+            Vcs::Darcs => "darcs",
+            Vcs::Fossil => "fossil",
+            Vcs::Git => "git",
+            Vcs::Hg => "hg",
+            Vcs::Svn => "svn",
+        }
+    }
+
+    /// Returns the version control system of a source URL protocol, which
+    /// are the ones supported by makepkg.
+    fn from_protocol(protocol: &str) -> Option<Vcs> {
+        match protocol {
+            "bzr" => Some(Vcs::Bzr),
+            "fossil" => Some(Vcs::Fossil),
+            "git" => Some(Vcs::Git),
+            "hg" => Some(Vcs::Hg),
+            "svn" => Some(Vcs::Svn),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Vcs {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The reference of a VCS source to build, from the fragment of its URL.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Fragment {
+    /// A branch, from `#branch=`.
+    Branch(String),
+    /// A commit, from `#commit=`.
+    Commit(String),
+    /// A revision, from `#revision=`.
+    Revision(String),
+    /// A tag, from `#tag=`.
+    Tag(String),
+}
+
+/// A source of a package that is fetched from version control.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Source {
+    /// The reference to build, or `None` to build the default one.
+    pub fragment: Option<Fragment>,
+    /// The directory the source is fetched into, from a `name::` prefix.
+    pub name: Option<String>,
+    /// Whether the reference to build must be signed, from a `?signed`
+    /// query.
+    pub signed: bool,
+    /// The URL of the repository, without the VCS prefix, fragment or query.
+    pub url: String,
+    /// The version control system of the source.
+    pub vcs: Vcs,
+}
+
+impl Source {
+    /// Parses a source the way makepkg does, returning `None` if it is not
+    /// fetched from version control.
+    ///
+    /// The version control system is taken from a `vcs+` prefix, such as in
+    /// `git+https://github.com/Jguer/yay`, or from the protocol, such as in
+    /// `git://git.example.org/foo`. A source with a fragment that makepkg
+    /// does not recognize is not considered a VCS source.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use aur::vcs::{Fragment, Source, Vcs};
+    ///
+    /// let source = Source::parse("yay::git+https://github.com/Jguer/yay#tag=v12.3.5?signed").unwrap();
+    ///
+    /// assert_eq!(source.vcs, Vcs::Git);
+    /// assert_eq!(source.url, "https://github.com/Jguer/yay");
+    /// assert_eq!(source.name.unwrap(), "yay");
+    /// assert_eq!(source.fragment, Some(Fragment::Tag("v12.3.5".to_owned())));
+    /// assert!(source.signed);
+    ///
+    /// assert!(Source::parse("https://example.org/foo-1.0.tar.gz").is_none());
+    /// ```
+    pub fn parse(source: &str) -> Option<Source> {
+        let (name, location) = match source.find("::") {
+            Some(idx) => (Some(source[..idx].to_owned()), &source[idx + 2..]),
+            None => (None, source),
+        };
+
+        let scheme_end = location.find("://")?;
+        let (vcs, url) = match location[..scheme_end].find('+') {
+            Some(idx) => (Vcs::from_protocol(&location[..idx])?, &location[idx + 1..]),
+            None => (Vcs::from_protocol(&location[..scheme_end])?, location),
+        };
+
+        let url_end = url.find(&['#', '?'][..]).unwrap_or(url.len());
+        let (url, rest) = url.split_at(url_end);
+        let (fragment, query) = match rest.find('?') {
+            Some(idx) => (&rest[..idx], &rest[idx + 1..]),
+            None => (rest, ""),
+        };
+
+        let fragment = if fragment.len() > 1 {
+            let mut parts = fragment[1..].splitn(2, '=');
+            let key = parts.next().unwrap_or("");
+            let value = parts.next().unwrap_or("").to_owned();
+
+            Some(match key {
+                "branch" => Fragment::Branch(value),
+                "commit" => Fragment::Commit(value),
+                "revision" => Fragment::Revision(value),
+                "tag" => Fragment::Tag(value),
+                _ => return None,
+            })
+        } else {
+            None
+        };
+
+        Some(Source {
+            fragment,
+            name,
+            signed: query == "signed",
+            url: url.to_owned(),
+            vcs,
+        })
+    }
+
+    /// Looks up the upstream commit that the source would currently be
+    /// built from, using `git ls-remote`.
+    ///
+    /// This is the commit of the branch or tag given by the fragment, or of
+    /// the remote `HEAD` if there is none. The commit of a `#commit=`
+    /// fragment is returned without looking it up, as it never changes.
+    ///
+    /// Returns `None` for sources of other version control systems, whose
+    /// upstream revisions can not be looked up.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Git`] if `git ls-remote` failed, such as for a
+    /// repository that does not exist, or if the branch or tag does not
+    /// exist.
+    ///
+    /// Returns [`Error::Io`] if git could not be run.
+    ///
+    /// [`Error::Git`]: ../enum.Error.html#variant.Git
+    /// [`Error::Io`]: ../enum.Error.html#variant.Io
+    pub fn upstream_commit(&self) -> Result<Option<String>> {
+        if self.vcs != Vcs::Git {
+            return Ok(None);
+        }
+
+        let refs = match self.fragment {
+            Some(Fragment::Commit(ref commit)) => return Ok(Some(commit.clone())),
+            Some(Fragment::Branch(ref branch)) => vec![format!("refs/heads/{}", branch)],
+            Some(Fragment::Tag(ref tag)) => {
+                // An annotated tag is listed along with the commit it points
+                // to, which is what is built.
+                vec![format!("refs/tags/{}^{{}}", tag), format!("refs/tags/{}", tag)]
+            },
+            Some(Fragment::Revision(_)) | None => vec!["HEAD".to_owned()],
+        };
+
+        let mut args = vec!["ls-remote", "--", &self.url[..]];
+        args.extend(refs.iter().map(|name| &name[..]));
+        let output = git::git(Path::new("."), &args)?;
+
+        let listed = output
+            .lines()
+            .filter_map(|line| line.find('\t').map(|idx| (&line[..idx], &line[idx + 1..])))
+            .collect::<Vec<_>>();
+
+        for name in &refs {
+            if let Some(&(commit, _)) = listed.iter().find(|listed| listed.1 == name) {
+                return Ok(Some(commit.to_owned()));
+            }
+        }
+
+        Err(Error::Git(format!("couldn't find remote ref {}", refs[refs.len() - 1])))
+    }
+}
+
+/// Returns the VCS sources of a `.SRCINFO`, for every architecture, in
+/// order.
+pub fn sources(srcinfo: &Srcinfo) -> Vec<Source> {
+    srcinfo.base.source
+        .iter()
+        .flat_map(|entry| entry.values.iter())
+        .filter_map(|source| Source::parse(source))
+        .collect()
+}
+
+/// Detects whether a package is built from version control, by the suffix
+/// of its name or otherwise by the first VCS source of its `.SRCINFO`.
+pub fn detect(name: &str, srcinfo: &Srcinfo) -> Option<Vcs> {
+    Vcs::from_name(name).or_else(|| sources(srcinfo).first().map(|source| source.vcs))
+}
@@ -0,0 +1,317 @@
+//! Package versions compared using the same rules as pacman.
+//!
+//! Package versions take the form `epoch:pkgver-pkgrel`, where the epoch and
+//! pkgrel are optional. Versions are compared as pacman's `vercmp` compares
+//! them:
+//!
+//! - epochs are compared first, with a missing epoch being `0`;
+//! - pkgvers are compared segment by segment, where numeric segments are
+//!   newer than alphabetic segments and separators only matter by their
+//!   length;
+//! - pkgrels are only compared when both versions have one.
+//!
+//! As a missing pkgrel matches any pkgrel, `vercmp` is not a total order:
+//! `1.0-1` and `1.0-2` both match `1.0`, but not each other. The [`Ord`] of
+//! [`Version`] is therefore the same except that a missing pkgrel is older
+//! than any pkgrel, which makes versions usable for sorting and as keys, while
+//! [`vercmp`] and [`Version::vercmp`] follow pacman for checking dependencies
+//! and upgrades.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aur::version::{vercmp, Version};
+//! use std::cmp::Ordering;
+//!
+//! assert!(Version::parse("1.0rc1") < Version::parse("1.0"));
+//! assert!(Version::parse("1:0.1") > Version::parse("2.0"));
+//! assert!(Version::parse("1.0") < Version::parse("1.0-1"));
+//! assert_eq!(vercmp("1.0-1", "1.0"), Ordering::Equal);
+//! ```
+//!
+//! [`Ord`]: struct.Version.html#impl-Ord
+//! [`Version`]: struct.Version.html
+//! [`Version::vercmp`]: struct.Version.html#method.vercmp
+//! [`vercmp`]: fn.vercmp.html
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// A package version of the form `epoch:pkgver-pkgrel`.
+///
+/// Equality and ordering follow pacman's `vercmp`, except that a missing
+/// pkgrel is older than any pkgrel so that they form a total order. Use
+/// [`vercmp`] to compare versions exactly as pacman does.
+///
+/// [`vercmp`]: #method.vercmp
+#[derive(Clone, Debug)]
+pub struct Version {
+    text: String,
+    epoch: Option<Range>,
+    pkgver: Range,
+    pkgrel: Option<Range>,
+}
+
+impl Version {
+    /// Parses a version string.
+    ///
+    /// Like pacman, parsing can not fail; every string is a valid version.
+    pub fn parse(text: &str) -> Self {
+        let (epoch, pkgver, pkgrel) = split(text);
+
+        Version {
+            text: text.to_owned(),
+            epoch,
+            pkgver,
+            pkgrel,
+        }
+    }
+
+    /// Returns the version as it was parsed.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the epoch of the version, if one was given.
+    pub fn epoch(&self) -> Option<&str> {
+        self.epoch.map(|(start, end)| &self.text[start..end])
+    }
+
+    /// Returns the epoch used for comparisons, where a missing or empty epoch
+    /// is `0`.
+    fn comparable_epoch(&self) -> &str {
+        match self.epoch() {
+            Some(epoch) if !epoch.is_empty() => epoch,
+            _ => "0",
+        }
+    }
+
+    /// Returns the pkgver of the version.
+    pub fn pkgver(&self) -> &str {
+        &self.text[self.pkgver.0..self.pkgver.1]
+    }
+
+    /// Returns the pkgrel of the version, if one was given.
+    pub fn pkgrel(&self) -> Option<&str> {
+        self.pkgrel.map(|(start, end)| &self.text[start..end])
+    }
+
+    /// Compares the version to another exactly as pacman's `vercmp` does,
+    /// where a missing pkgrel matches any pkgrel.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use aur::version::Version;
+    /// use std::cmp::Ordering;
+    ///
+    /// let version = Version::parse("1.0-2");
+    ///
+    /// assert_eq!(version.vercmp(&Version::parse("1.0")), Ordering::Equal);
+    /// assert_eq!(version.vercmp(&Version::parse("1.0-1")), Ordering::Greater);
+    /// ```
+    pub fn vercmp(&self, other: &Version) -> Ordering {
+        self.compare(other, |left, right| match (left, right) {
+            (Some(left), Some(right)) => rpmvercmp(left, right),
+            _ => Ordering::Equal,
+        })
+    }
+
+    /// Compares epochs and pkgvers, and then pkgrels with the given function.
+    fn compare<F>(&self, other: &Version, pkgrels: F) -> Ordering
+        where F: FnOnce(Option<&str>, Option<&str>) -> Ordering {
+        if self.text == other.text {
+            return Ordering::Equal;
+        }
+
+        rpmvercmp(self.comparable_epoch(), other.comparable_epoch())
+            .then_with(|| rpmvercmp(self.pkgver(), other.pkgver()))
+            .then_with(|| pkgrels(self.pkgrel(), other.pkgrel()))
+    }
+}
+
+impl<'a> From<&'a str> for Version {
+    fn from(text: &'a str) -> Self {
+        Version::parse(text)
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(&self.text)
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare(other, |left, right| match (left, right) {
+            (Some(left), Some(right)) => rpmvercmp(left, right),
+            (left, right) => left.is_some().cmp(&right.is_some()),
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+/// Compares two version strings exactly as pacman's `vercmp` does.
+///
+/// # Examples
+///
+/// ```rust
+/// use aur::version::vercmp;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(vercmp("1.5.b", "1.5.a"), Ordering::Greater);
+/// assert_eq!(vercmp("2.0", "2_0"), Ordering::Equal);
+/// assert_eq!(vercmp("1.0", "1.0-1"), Ordering::Equal);
+/// ```
+pub fn vercmp(left: &str, right: &str) -> Ordering {
+    Version::parse(left).vercmp(&Version::parse(right))
+}
+
+/// A range of bytes within a version string.
+type Range = (usize, usize);
+
+/// Splits a version into the byte ranges of its epoch, pkgver, and pkgrel,
+/// as pacman's `parseEVR` does.
+fn split(text: &str) -> (Option<Range>, Range, Option<Range>) {
+    let bytes = text.as_bytes();
+    let digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+
+    let (epoch, pkgver_start) = if bytes.get(digits) == Some(&b':') {
+        (Some((0, digits)), digits + 1)
+    } else {
+        (None, 0)
+    };
+
+    // As in pacman, the pkgrel separator is the last '-' after the leading
+    // digits.
+    match text[digits..].rfind('-').map(|idx| digits + idx) {
+        Some(idx) if idx >= pkgver_start => {
+            (epoch, (pkgver_start, idx), Some((idx + 1, text.len())))
+        },
+        _ => (epoch, (pkgver_start, text.len()), None),
+    }
+}
+
+/// A port of pacman's `rpmvercmp`, comparing a single version component.
+fn rpmvercmp(left: &str, right: &str) -> Ordering {
+    if left == right {
+        return Ordering::Equal;
+    }
+
+    let one = left.as_bytes();
+    let two = right.as_bytes();
+
+    // Indices to the start of the current segment and the end of the previous
+    // segment in each string.
+    let (mut i, mut j) = (0, 0);
+    let (mut prev_i, mut prev_j) = (0, 0);
+
+    while i < one.len() && j < two.len() {
+        while i < one.len() && !one[i].is_ascii_alphanumeric() {
+            i += 1;
+        }
+
+        while j < two.len() && !two[j].is_ascii_alphanumeric() {
+            j += 1;
+        }
+
+        if i >= one.len() || j >= two.len() {
+            break;
+        }
+
+        // Separators of different lengths decide the comparison.
+        if i - prev_i != j - prev_j {
+            return (i - prev_i).cmp(&(j - prev_j));
+        }
+
+        let is_num = one[i].is_ascii_digit();
+        let matches: fn(&u8) -> bool = if is_num {
+            |b| b.is_ascii_digit()
+        } else {
+            |b| b.is_ascii_alphabetic()
+        };
+
+        let end_i = i + one[i..].iter().take_while(|b| matches(b)).count();
+        let end_j = j + two[j..].iter().take_while(|b| matches(b)).count();
+
+        // Segments of different types: numeric segments are always newer
+        // than alphabetic ones.
+        if end_j == j {
+            return if is_num {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        let mut seg_one = &one[i..end_i];
+        let mut seg_two = &two[j..end_j];
+
+        if is_num {
+            while seg_one.first() == Some(&b'0') {
+                seg_one = &seg_one[1..];
+            }
+
+            while seg_two.first() == Some(&b'0') {
+                seg_two = &seg_two[1..];
+            }
+
+            // Whichever number has more digits wins.
+            match seg_one.len().cmp(&seg_two.len()) {
+                Ordering::Equal => {},
+                other => return other,
+            }
+        }
+
+        match seg_one.cmp(seg_two) {
+            Ordering::Equal => {},
+            other => return other,
+        }
+
+        i = end_i;
+        j = end_j;
+        prev_i = end_i;
+        prev_j = end_j;
+    }
+
+    let rest_one = &one[i..];
+    let rest_two = &two[j..];
+
+    // All segments compared equal but the separators differed.
+    if rest_one.is_empty() && rest_two.is_empty() {
+        return Ordering::Equal;
+    }
+
+    // A remaining alphabetic segment never beats an empty string, while a
+    // remaining numeric segment always does.
+    let one_alpha = is_alpha(rest_one.first());
+    let two_alpha = is_alpha(rest_two.first());
+
+    if (rest_one.is_empty() && !two_alpha) || one_alpha {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+#[inline]
+fn is_alpha(byte: Option<&u8>) -> bool {
+    match byte {
+        Some(byte) => byte.is_ascii_alphabetic(),
+        None => false,
+    }
+}
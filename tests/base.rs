@@ -0,0 +1,71 @@
+extern crate aur;
+extern crate serde_json;
+
+mod common;
+
+use aur::base::Base;
+use aur::dependency::Dependency;
+use aur::model::InfoResult;
+
+fn names(packages: &[&InfoResult]) -> Vec<String> {
+    packages.iter().map(|package| package.name.clone()).collect()
+}
+
+fn parse(dependencies: &[&str]) -> Vec<Dependency> {
+    dependencies.iter().map(|dependency| Dependency::parse(dependency)).collect()
+}
+
+#[test]
+fn test_group() {
+    let packages = common::packages("base.json");
+    let given = vec![
+        packages[0].clone(),
+        packages[3].clone(),
+        packages[1].clone(),
+        packages[0].clone(),
+    ];
+    let bases = Base::group(given, &["foo-docs", "foo", "bar-git"]);
+
+    assert_eq!(bases.len(), 2);
+
+    assert_eq!(bases[0].name(), "foo-base");
+    assert_eq!(bases[0].id(), 900);
+    assert_eq!(bases[0].version(), "1.2-1");
+    assert_eq!(names(&bases[0].packages().iter().collect::<Vec<_>>()), vec!["foo", "foo-docs"]);
+    assert_eq!(names(&bases[0].requested()), vec!["foo", "foo-docs"]);
+    assert!(bases[0].is_requested("foo-docs"));
+
+    assert_eq!(bases[1].name(), "bar");
+    assert!(bases[1].requested().is_empty());
+    assert!(!bases[1].is_requested("bar"));
+    assert!(!bases[1].is_requested("foo"));
+}
+
+#[test]
+fn test_requested_duplicate() {
+    let packages = common::packages("base.json");
+    let given = vec![packages[2].clone(), packages[2].clone()];
+    let bases = Base::group(given, &["foo"]);
+
+    assert_eq!(bases[0].packages().len(), 1);
+    assert!(bases[0].requested().is_empty());
+
+    let given = vec![packages[1].clone(), packages[0].clone(), packages[1].clone()];
+    let bases = Base::group(given, &["foo-docs"]);
+
+    assert_eq!(names(&bases[0].requested()), vec!["foo-docs"]);
+}
+
+#[test]
+fn test_merged_dependencies() {
+    let packages = common::packages("base.json");
+    let bases = Base::group(packages, &["foo"]);
+
+    assert_eq!(bases[0].dependencies(), parse(&["glibc", "zlib"]));
+    assert_eq!(bases[0].make_depends(), parse(&["cmake", "doxygen"]));
+    assert_eq!(bases[0].check_depends(), parse(&["gtest"]));
+
+    // The provision satisfies bar's first dependency, but foo-libs is too
+    // old for its second.
+    assert_eq!(bases[1].dependencies(), parse(&["libfoo.so=1-64", "foo-libs>=2"]));
+}
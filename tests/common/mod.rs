@@ -0,0 +1,54 @@
+//! Helpers shared by the integration tests.
+
+// Each test crate only uses some of the helpers.
+#![allow(dead_code)]
+
+use aur::model::{InfoResult, Search};
+use serde_json;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Reads the results of an info response from a fixture, such as
+/// `"resolve.json"`.
+pub fn packages(fixture: &str) -> Vec<InfoResult> {
+    let json = fs::read_to_string(format!("tests/fixtures/{}", fixture)).unwrap();
+    let search: Search<InfoResult> = serde_json::from_str(&json).unwrap();
+
+    search.results
+}
+
+/// Looks up packages by name, as an info request would.
+pub fn lookup(packages: &[InfoResult], names: &[String]) -> Vec<InfoResult> {
+    packages
+        .iter()
+        .filter(|package| names.contains(&package.name))
+        .cloned()
+        .collect()
+}
+
+/// Runs git in a directory with a committer identity, returning its trimmed
+/// output.
+pub fn git(directory: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(["-c", "user.name=aur", "-c", "user.email=aur@localhost"])
+        .args(args)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    String::from_utf8(output.stdout).unwrap().trim().to_owned()
+}
+
+/// Commits a file with the given contents to a repository, returning the
+/// commit.
+pub fn commit(repo: &Path, file: &str, contents: &str) -> String {
+    fs::write(repo.join(file), contents).unwrap();
+    git(repo, &["add", file]);
+    git(repo, &["commit", "--quiet", "-m", contents]);
+
+    git(repo, &["rev-parse", "HEAD"])
+}
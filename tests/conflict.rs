@@ -0,0 +1,95 @@
+extern crate aur;
+extern crate serde_json;
+
+mod common;
+
+use aur::conflict::{self, Conflict, InstalledPackage, Member, Rule};
+use aur::dependency::Dependency;
+use aur::model::InfoResult;
+
+fn planned() -> Vec<InfoResult> {
+    common::packages("conflict.json")
+}
+
+fn installed() -> Vec<InstalledPackage> {
+    let mut gvim = InstalledPackage::new("gvim", "9.1-1");
+    gvim.conflicts.push("vim-plugin".to_owned());
+    gvim.provides.push("vim=9.1".to_owned());
+
+    vec![
+        InstalledPackage::new("foo", "1.0-1"),
+        InstalledPackage::new("libold", "2.1-1"),
+        InstalledPackage::new("oldqux", "1-1"),
+        InstalledPackage::new("qux", "1-1"),
+        gvim,
+    ]
+}
+
+fn planned_member(name: &str) -> Member {
+    Member::Planned(name.to_owned())
+}
+
+fn installed_member(name: &str) -> Member {
+    Member::Installed(name.to_owned())
+}
+
+#[test]
+fn test_analyze() {
+    let planned = planned();
+    let conflicts = conflict::analyze(&planned, &installed());
+
+    assert_eq!(conflicts, vec![
+        Conflict {
+            other: installed_member("foo"),
+            package: planned_member("foo-git"),
+            rule: Rule::Conflicts(Dependency::parse("foo")),
+        },
+        Conflict {
+            other: planned_member("baz"),
+            package: planned_member("bar"),
+            rule: Rule::ConflictsProvision(
+                Dependency::parse("libold<2"),
+                Dependency::parse("libold=1.5"),
+            ),
+        },
+        Conflict {
+            other: installed_member("oldqux"),
+            package: planned_member("qux"),
+            rule: Rule::Replaces(Dependency::parse("oldqux")),
+        },
+        Conflict {
+            other: planned_member("vim-plugin"),
+            package: installed_member("gvim"),
+            rule: Rule::Conflicts(Dependency::parse("vim-plugin")),
+        },
+    ]);
+}
+
+#[test]
+fn test_planned_only() {
+    let planned = planned();
+    let conflicts = conflict::analyze(&planned[..3], &[]);
+
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(
+        conflicts[0].to_string(),
+        "bar conflicts with baz: conflicts libold<2, provided as libold=1.5",
+    );
+}
+
+#[test]
+fn test_provision_conflict_with_installed() {
+    let planned = planned();
+    let mut foo = InstalledPackage::new("foo-bin", "2-1");
+    foo.conflicts.push("foo".to_owned());
+
+    let conflicts = conflict::analyze(&planned[..1], &[foo]);
+
+    assert_eq!(conflicts.len(), 1);
+    assert!(conflicts[0].package.is_installed());
+    assert_eq!(conflicts[0].package.name(), "foo-bin");
+    assert_eq!(
+        conflicts[0].to_string(),
+        "foo-bin (installed) conflicts with foo-git: conflicts foo, provided as foo=1.2",
+    );
+}
@@ -0,0 +1,76 @@
+extern crate aur;
+extern crate serde_json;
+
+use aur::dependency::{Dependency, Operator, OptionalDependency};
+use aur::model::{InfoResult, Search};
+
+fn constraint(text: &str) -> Option<(String, Operator, String)> {
+    let Dependency { name, version } = Dependency::parse(text);
+
+    version.map(|version| (name, version.operator, version.version))
+}
+
+#[test]
+fn test_parse_operators() {
+    assert_eq!(constraint("foo<1.0"), Some(("foo".to_owned(), Operator::Less, "1.0".to_owned())));
+    assert_eq!(constraint("foo<=1.0"), Some(("foo".to_owned(), Operator::LessEqual, "1.0".to_owned())));
+    assert_eq!(constraint("foo=1.0"), Some(("foo".to_owned(), Operator::Equal, "1.0".to_owned())));
+    assert_eq!(constraint("foo>=1:1.0-2"), Some(("foo".to_owned(), Operator::GreaterEqual, "1:1.0-2".to_owned())));
+    assert_eq!(constraint("foo>1.0"), Some(("foo".to_owned(), Operator::Greater, "1.0".to_owned())));
+}
+
+#[test]
+fn test_parse_unversioned() {
+    let dependency = Dependency::parse("libfoo.so");
+
+    assert_eq!(dependency.name, "libfoo.so");
+    assert!(dependency.version.is_none());
+}
+
+#[test]
+fn test_display_round_trips() {
+    for text in &["foo", "foo<1", "foo<=1", "foo=1", "foo>=1", "foo>1", "libfoo.so=1-64"] {
+        assert_eq!(Dependency::parse(text).to_string(), *text);
+    }
+
+    for text in &["foo", "foo: for bar support", "foo>=2: with a: colon"] {
+        assert_eq!(OptionalDependency::parse(text).to_string(), *text);
+    }
+}
+
+#[test]
+fn test_parse_optional() {
+    let optional = OptionalDependency::parse("python-foo>=2: for the foo backend");
+
+    assert_eq!(optional.dependency.name, "python-foo");
+    assert_eq!(optional.dependency.version.unwrap().operator, Operator::GreaterEqual);
+    assert_eq!(optional.description.unwrap(), "for the foo backend");
+
+    let optional = OptionalDependency::parse("sudo");
+
+    assert_eq!(optional.dependency.name, "sudo");
+    assert!(optional.description.is_none());
+}
+
+#[test]
+fn test_info_accessors() {
+    let search: Search<InfoResult> = serde_json::from_str(include_str!("fixtures/info.json")).unwrap();
+
+    let yay = &search.results[0];
+    let depends = yay.parsed_dependencies();
+    assert_eq!(depends[0].name, "pacman");
+    assert_eq!(depends[0].version.as_ref().unwrap().operator, Operator::Greater);
+    assert_eq!(depends[1], Dependency::parse("git"));
+    assert_eq!(yay.parsed_make_depends()[0].version.as_ref().unwrap().version, "1.21");
+    assert_eq!(yay.parsed_check_depends(), vec![Dependency::parse("go")]);
+
+    let optional = yay.parsed_optional_dependencies();
+    assert_eq!(optional.len(), 2);
+    assert_eq!(optional[1].dependency.name, "doas");
+    assert_eq!(optional[1].description.as_ref().unwrap(), "privilege elevation");
+
+    let rust = &search.results[1];
+    assert_eq!(rust.parsed_provides()[1].to_string(), "cargo=1.77.0");
+    assert_eq!(rust.parsed_replaces()[0].name, "rust-nightly-bin");
+    assert!(rust.parsed_conflicts().is_empty());
+}
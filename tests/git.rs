@@ -0,0 +1,88 @@
+extern crate aur;
+extern crate serde_json;
+extern crate tempfile;
+
+mod common;
+
+use aur::git::Cache;
+use aur::{Endpoint, Error};
+use std::fs;
+
+#[test]
+fn test_clone_and_fast_forward() {
+    let dir = tempfile::tempdir().unwrap();
+    let work = dir.path().join("work");
+    let remotes = dir.path().join("remotes");
+    let remote = remotes.join("yay.git");
+
+    fs::create_dir_all(&work).unwrap();
+    common::git(&work, &["init", "--quiet"]);
+    let first = common::commit(&work, "PKGBUILD", "pkgver=1");
+    common::git(dir.path(), &["clone", "--quiet", "--bare", work.to_str().unwrap(), remote.to_str().unwrap()]);
+
+    let endpoint = Endpoint::new(format!("file://{}", remotes.display()));
+    let cache = Cache::with_endpoint(dir.path().join("cache"), endpoint);
+
+    let update = cache.sync("yay").unwrap();
+    assert_eq!(update.path, cache.path("yay").unwrap());
+    assert_eq!(update.old_head, None);
+    assert_eq!(update.new_head, first);
+    assert!(update.is_cloned());
+    assert!(update.is_changed());
+
+    let update = cache.sync("yay").unwrap();
+    assert_eq!(update.old_head.as_ref(), Some(&first));
+    assert_eq!(update.new_head, first);
+    assert!(!update.is_cloned());
+    assert!(!update.is_changed());
+
+    let second = common::commit(&work, "PKGBUILD", "pkgver=2");
+    common::git(&work, &["push", "--quiet", remote.to_str().unwrap(), "HEAD"]);
+
+    let update = cache.sync("yay").unwrap();
+    assert_eq!(update.old_head, Some(first));
+    assert_eq!(update.new_head, second);
+    assert!(update.is_changed());
+    assert_eq!(fs::read_to_string(update.path.join("PKGBUILD")).unwrap(), "pkgver=2");
+}
+
+#[test]
+fn test_missing_package_base() {
+    let dir = tempfile::tempdir().unwrap();
+    let endpoint = Endpoint::new(format!("file://{}", dir.path().display()));
+    let cache = Cache::with_endpoint(dir.path().join("cache"), endpoint);
+
+    match cache.sync("missing") {
+        Err(Error::Git(ref message)) => assert!(!message.is_empty()),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_package_base() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = Cache::new(dir.path().join("cache"));
+
+    for &package_base in &["", ".", "..", "../escape", "yay/../../escape"] {
+        match cache.sync(package_base) {
+            Err(Error::InvalidPackageBase(ref name)) => assert_eq!(name, package_base),
+            other => panic!("{:?}: unexpected result {:?}", package_base, other),
+        }
+    }
+
+    assert!(!dir.path().join("cache").exists());
+}
+
+#[test]
+fn test_url_is_not_an_option() {
+    let dir = tempfile::tempdir().unwrap();
+    let endpoint = Endpoint::new("--upload-pack=touch pwned;true ");
+    let cache = Cache::with_endpoint(dir.path().join("cache"), endpoint);
+
+    // The URL is reported as a repository that does not exist, rather than
+    // being taken as an option.
+    match cache.sync("yay") {
+        Err(Error::Git(ref message)) => assert!(message.contains("--upload-pack"), "{}", message),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
@@ -0,0 +1,78 @@
+extern crate aur;
+extern crate serde_json;
+
+use aur::health::{PackageHealth, Staleness, Tier};
+use aur::model::{InfoResult, Search, SearchResult};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DAY: u64 = 60 * 60 * 24;
+
+fn info() -> Search<InfoResult> {
+    serde_json::from_str(include_str!("fixtures/info.json")).unwrap()
+}
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+#[test]
+fn test_maintained_package() {
+    let search = info();
+    let health = PackageHealth::assess_at(&search.results[0], at(1734384911 + 10 * DAY));
+
+    assert_eq!(health, PackageHealth {
+        orphaned: false,
+        out_of_date_for: None,
+        since_update: Duration::from_secs(10 * DAY),
+        staleness: Staleness::Fresh,
+        popularity: Tier::High,
+        votes: Tier::High,
+        vcs: false,
+    });
+}
+
+#[test]
+fn test_neglected_package() {
+    let search = info();
+    let now = at(1710000000 + 400 * DAY);
+    let health = PackageHealth::assess_at(&search.results[1], now);
+
+    assert!(health.orphaned);
+    assert_eq!(health.out_of_date_for, Some(Duration::from_secs(400 * DAY)));
+    assert_eq!(health.since_update, Duration::from_secs(1710000000 - 1700000000 + 400 * DAY));
+    assert_eq!(health.staleness, Staleness::Stale);
+    assert_eq!(health.popularity, Tier::Low);
+    assert_eq!(health.votes, Tier::Medium);
+    assert!(!health.vcs);
+}
+
+#[test]
+fn test_vcs_package() {
+    let search: Search<SearchResult> =
+        serde_json::from_str(include_str!("fixtures/search.json")).unwrap();
+    let vcs = search.results
+        .iter()
+        .map(|result| PackageHealth::assess(result).vcs)
+        .collect::<Vec<_>>();
+
+    assert_eq!(vcs, vec![false, true, false]);
+}
+
+#[test]
+fn test_tiers() {
+    assert_eq!(Tier::from_popularity(0.0), Tier::None);
+    assert_eq!(Tier::from_popularity(0.5), Tier::Low);
+    assert_eq!(Tier::from_popularity(1.0), Tier::Medium);
+    assert_eq!(Tier::from_popularity(10.0), Tier::High);
+    assert_eq!(Tier::from_votes(0), Tier::None);
+    assert_eq!(Tier::from_votes(9), Tier::Low);
+    assert_eq!(Tier::from_votes(10), Tier::Medium);
+    assert_eq!(Tier::from_votes(100), Tier::High);
+}
+
+#[test]
+fn test_staleness() {
+    assert_eq!(Staleness::from_duration(Duration::from_secs(89 * DAY)), Staleness::Fresh);
+    assert_eq!(Staleness::from_duration(Duration::from_secs(90 * DAY)), Staleness::Aging);
+    assert_eq!(Staleness::from_duration(Duration::from_secs(365 * DAY)), Staleness::Stale);
+}
@@ -1,20 +1,81 @@
 extern crate aur;
+extern crate flate2;
 extern crate futures;
 extern crate hyper;
 extern crate hyper_tls;
+extern crate tar;
+extern crate tempfile;
 extern crate tokio;
+#[cfg(feature = "tracing")]
+extern crate tracing;
 
-use aur::AurHyperRequester;
+use aur::{AurHyperRequester, Endpoint, Error};
+use aur::model::SearchBy;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use futures::Future;
 use hyper::client::HttpConnector;
 use hyper::{Body, Client};
 use hyper_tls::HttpsConnector;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+use tar::{Builder, Header};
+use tokio::runtime::current_thread::Runtime;
 
 #[inline]
 fn client() -> Client<HttpsConnector<HttpConnector>, Body> {
 	Client::builder().build(HttpsConnector::new(4).unwrap())
 }
 
+/// Serves a single request on a local port with the given response body,
+/// returning an endpoint for the server and a handle joining to the request
+/// line that was received.
+fn serve(body: Vec<u8>) -> (Endpoint, JoinHandle<String>) {
+	let length = body.len();
+
+	serve_response("200 OK", length, body)
+}
+
+/// Serves a single request with the given status and response body, which
+/// is cut short if the given content length is larger than it.
+fn serve_response(status: &'static str, length: usize, body: Vec<u8>)
+	-> (Endpoint, JoinHandle<String>) {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let endpoint = Endpoint::new(format!("http://{}", listener.local_addr().unwrap()));
+
+	let handle = thread::spawn(move || {
+		let (stream, _) = listener.accept().unwrap();
+		let mut reader = BufReader::new(stream);
+
+		let mut request_line = String::new();
+		reader.read_line(&mut request_line).unwrap();
+
+		let mut header = String::new();
+		while reader.read_line(&mut header).unwrap() > 2 {
+			header.clear();
+		}
+
+		let mut stream = reader.into_inner();
+		write!(
+			stream,
+			"HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+			status,
+			length,
+		).unwrap();
+		stream.write_all(&body).unwrap();
+
+		request_line.trim_end().to_owned()
+	});
+
+	(endpoint, handle)
+}
+
+fn fixture(name: &str) -> Vec<u8> {
+	fs::read(format!("tests/fixtures/{}", name)).unwrap()
+}
+
 #[test]
 fn test_info() {
 	let done = client().aur_info(&["rust-nightly"]).map(|search| {
@@ -36,3 +97,216 @@ fn test_search() {
 
 	tokio::run(done);
 }
+
+#[test]
+fn test_download_snapshot() {
+	let dir = tempfile::tempdir().unwrap();
+
+	let done = client().aur_download_snapshot("rust-nightly", dir.path()).map(|path| {
+        assert!(path.join("PKGBUILD").is_file());
+    }).map_err(|why| {
+        panic!("Err downloading snapshot: {:?}", why);
+    });
+
+	tokio::run(done);
+}
+
+#[test]
+fn test_download_snapshot_at_endpoint() {
+	let mut builder = Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+	let mut header = Header::new_gnu();
+	header.set_size(12);
+	header.set_mode(0o644);
+	header.set_cksum();
+	builder.append_data(&mut header, "yay/PKGBUILD", &b"pkgname=yay\n"[..]).unwrap();
+	let tarball = builder.into_inner().unwrap().finish().unwrap();
+
+	let (endpoint, server) = serve(tarball);
+	let dir = tempfile::tempdir().unwrap();
+
+	let mut runtime = Runtime::new().unwrap();
+	let path = runtime.block_on(client().aur_download_snapshot_at(&endpoint, "yay", dir.path())).unwrap();
+
+	assert_eq!(path, dir.path().join("yay"));
+	assert_eq!(fs::read_to_string(path.join("PKGBUILD")).unwrap(), "pkgname=yay\n");
+	assert!(dir.path().join("yay.tar.gz").is_file());
+	assert_eq!(server.join().unwrap(), "GET /cgit/aur.git/snapshot/yay.tar.gz HTTP/1.1");
+}
+
+#[test]
+fn test_download_snapshot_unsuccessful_status() {
+	let (endpoint, server) = serve_response("404 Not Found", 9, b"Not found".to_vec());
+	let dir = tempfile::tempdir().unwrap();
+
+	let mut runtime = Runtime::new().unwrap();
+
+	match runtime.block_on(client().aur_download_snapshot_at(&endpoint, "missing", dir.path())) {
+		Err(Error::Status(404)) => {},
+		other => panic!("unexpected result {:?}", other),
+	}
+
+	assert!(!dir.path().join("missing.tar.gz").exists());
+	server.join().unwrap();
+}
+
+#[test]
+fn test_download_snapshot_cut_short() {
+	let (endpoint, server) = serve_response("200 OK", 4096, vec![0x1f, 0x8b, 0x08, 0x00]);
+	let dir = tempfile::tempdir().unwrap();
+
+	let mut runtime = Runtime::new().unwrap();
+
+	// The partial tarball is never extracted.
+	match runtime.block_on(client().aur_download_snapshot_at(&endpoint, "yay", dir.path())) {
+		Err(Error::Hyper(_)) => {},
+		other => panic!("unexpected result {:?}", other),
+	}
+
+	assert!(!dir.path().join("yay").exists());
+	server.join().unwrap();
+}
+
+#[test]
+fn test_download_snapshot_invalid_package_base() {
+	let dir = tempfile::tempdir().unwrap();
+
+	for &package_base in &["", ".", "..", "../escape", "yay/../../escape"] {
+		// The name is rejected before any request is sent.
+		match client().aur_download_snapshot(package_base, dir.path()).wait() {
+			Err(Error::InvalidPackageBase(ref name)) => assert_eq!(name, package_base),
+			other => panic!("{:?}: unexpected result {:?}", package_base, other),
+		}
+	}
+}
+
+#[test]
+fn test_search_by() {
+	let done = client().aur_search_by("java-runtime", SearchBy::Provides).map(|search| {
+        assert!(search.result_count >= 1);
+    }).map_err(|why| {
+        panic!("Err searching by provides: {:?}", why);
+    });
+
+	tokio::run(done);
+}
+
+#[test]
+fn test_info_at_endpoint() {
+	let (endpoint, server) = serve(fixture("info.json"));
+
+	let done = client().aur_info_at(&endpoint, &["yay", "yay-bin"]).map(|search| {
+		assert_eq!(search.result_count, 2);
+	}).map_err(|why| {
+		panic!("Err testing info: {:?}", why);
+	});
+
+	tokio::run(done);
+
+	assert_eq!(server.join().unwrap(), "GET /rpc?v=5&type=info&arg[]=yay&arg[]=yay-bin HTTP/1.1");
+}
+
+#[test]
+fn test_search_by_at_endpoint() {
+	let (endpoint, server) = serve(fixture("search.json"));
+
+	let done = client().aur_search_by_at(&endpoint, "yay", SearchBy::Name).map(|search| {
+		assert_eq!(search.result_count, 3);
+	}).map_err(|why| {
+		panic!("Err searching by name: {:?}", why);
+	});
+
+	tokio::run(done);
+
+	assert_eq!(server.join().unwrap(), "GET /rpc?v=5&type=search&by=name&arg=yay HTTP/1.1");
+}
+
+#[cfg(feature = "tracing")]
+mod tracing_support {
+	use aur::AurHyperRequester;
+	use std::collections::HashMap;
+	use std::fmt::Debug;
+	use std::fs;
+	use std::sync::{Arc, Mutex};
+	use super::{client, fixture, serve};
+	use tokio::runtime::current_thread::Runtime;
+	use tracing::field::{Field, Visit};
+	use tracing::span::{Attributes, Id, Record};
+	use tracing::{self, Event, Metadata, Subscriber};
+
+	/// The fields recorded on the request span, and the number of times it
+	/// was entered.
+	#[derive(Default)]
+	struct Recorded {
+		entered: usize,
+		fields: HashMap<String, String>,
+	}
+
+	impl Visit for Recorded {
+		fn record_str(&mut self, field: &Field, value: &str) {
+			self.fields.insert(field.name().to_owned(), value.to_owned());
+		}
+
+		fn record_debug(&mut self, field: &Field, value: &Debug) {
+			self.fields.insert(field.name().to_owned(), format!("{:?}", value));
+		}
+	}
+
+	/// A subscriber recording the request span of a bridge.
+	struct Recorder(Arc<Mutex<Recorded>>);
+
+	impl Subscriber for Recorder {
+		fn enabled(&self, metadata: &Metadata) -> bool {
+			metadata.target().starts_with("aur")
+		}
+
+		fn new_span(&self, span: &Attributes) -> Id {
+			span.record(&mut *self.0.lock().unwrap());
+
+			Id::from_u64(1)
+		}
+
+		fn record(&self, _: &Id, values: &Record) {
+			values.record(&mut *self.0.lock().unwrap());
+		}
+
+		fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+		fn event(&self, _: &Event) {}
+
+		fn enter(&self, _: &Id) {
+			self.0.lock().unwrap().entered += 1;
+		}
+
+		fn exit(&self, _: &Id) {}
+	}
+
+	#[test]
+	fn test_info_span() {
+		let (endpoint, server) = serve(fixture("info.json"));
+		let recorded = Arc::new(Mutex::new(Recorded::default()));
+
+		let search = tracing::subscriber::with_default(Recorder(recorded.clone()), || {
+			let mut runtime = Runtime::new().unwrap();
+
+			runtime.block_on(client().aur_info_at(&endpoint, &["yay", "yay-bin"]))
+		}).unwrap();
+
+		assert_eq!(search.result_count, 2);
+		server.join().unwrap();
+
+		let recorded = recorded.lock().unwrap();
+		let size = fs::metadata("tests/fixtures/info.json").unwrap().len();
+		let url = format!("{}&type=info&arg[]=yay&arg[]=yay-bin", endpoint.rpc_url());
+
+		assert_eq!(recorded.fields["request_type"], "info");
+		assert_eq!(recorded.fields["arg_count"], "2");
+		assert_eq!(recorded.fields["url"], url);
+		assert_eq!(recorded.fields["status"], "200");
+		assert_eq!(recorded.fields["response_size"], size.to_string());
+		assert_eq!(recorded.fields["result_count"], "2");
+		assert!(recorded.fields.contains_key("latency_ms"));
+		assert_eq!(recorded.fields["retry_count"], "0");
+		// The span is entered while the request future is polled.
+		assert!(recorded.entered > 0);
+	}
+}
@@ -0,0 +1,82 @@
+extern crate aur;
+extern crate serde_json;
+
+use aur::license::{self, License, LicenseKind};
+use aur::model::{InfoResult, Search};
+
+fn normalize(text: &str) -> (Option<String>, LicenseKind) {
+    let license = License::parse(text);
+
+    (license.spdx, license.kind)
+}
+
+fn spdx(id: &str) -> Option<String> {
+    Some(id.to_owned())
+}
+
+#[test]
+fn test_arch_common_licenses() {
+    assert_eq!(normalize("GPL"), (spdx("GPL-2.0-or-later"), LicenseKind::Free));
+    assert_eq!(normalize("GPL2"), (spdx("GPL-2.0-only"), LicenseKind::Free));
+    assert_eq!(normalize("GPL3"), (spdx("GPL-3.0-only"), LicenseKind::Free));
+    assert_eq!(normalize("LGPL2.1"), (spdx("LGPL-2.1-only"), LicenseKind::Free));
+    assert_eq!(normalize("APACHE"), (spdx("Apache-2.0"), LicenseKind::Free));
+    assert_eq!(normalize("PerlArtistic"), (spdx("Artistic-1.0-Perl"), LicenseKind::Free));
+    assert_eq!(normalize("MPL2"), (spdx("MPL-2.0"), LicenseKind::Free));
+    assert_eq!(normalize("ZLIB"), (spdx("Zlib"), LicenseKind::Free));
+}
+
+#[test]
+fn test_spdx_identifiers() {
+    assert_eq!(normalize("GPL-3.0-or-later"), (spdx("GPL-3.0-or-later"), LicenseKind::Free));
+    assert_eq!(normalize("apache-2.0"), (spdx("Apache-2.0"), LicenseKind::Free));
+    assert_eq!(normalize("SSPL-1.0"), (spdx("SSPL-1.0"), LicenseKind::NonFree));
+    assert_eq!(normalize("CC-BY-NC-SA-4.0"), (spdx("CC-BY-NC-SA-4.0"), LicenseKind::NonFree));
+    assert_eq!(normalize("LicenseRef-Foo"), (spdx("LicenseRef-Foo"), LicenseKind::Unknown));
+    assert_eq!(normalize("LicenseRef-proprietary"), (spdx("LicenseRef-proprietary"), LicenseKind::NonFree));
+}
+
+#[test]
+fn test_custom_licenses() {
+    assert_eq!(normalize("custom:MIT"), (spdx("MIT"), LicenseKind::Free));
+    assert_eq!(normalize("custom:BSD3"), (spdx("BSD-3-Clause"), LicenseKind::Free));
+    assert_eq!(normalize("custom:proprietary"), (spdx("LicenseRef-proprietary"), LicenseKind::NonFree));
+    assert_eq!(normalize("custom:Commercial"), (spdx("LicenseRef-proprietary"), LicenseKind::NonFree));
+    assert_eq!(normalize("custom:Foo Public License"), (None, LicenseKind::Unknown));
+    assert_eq!(normalize("custom"), (None, LicenseKind::Unknown));
+    assert_eq!(normalize("unknown"), (None, LicenseKind::Unknown));
+    assert_eq!(normalize("CCPL:by-nc-sa"), (None, LicenseKind::NonFree));
+    assert_eq!(normalize("CCPL:by-sa"), (None, LicenseKind::Free));
+}
+
+#[test]
+fn test_expressions() {
+    assert_eq!(normalize("GPL2 OR MIT"), (spdx("GPL-2.0-only OR MIT"), LicenseKind::Free));
+    assert_eq!(normalize("MIT AND SSPL-1.0"), (spdx("MIT AND SSPL-1.0"), LicenseKind::NonFree));
+    assert_eq!(normalize("SSPL-1.0 OR MIT"), (spdx("SSPL-1.0 OR MIT"), LicenseKind::Free));
+    assert_eq!(
+        normalize("(apache-2.0 OR mit) AND GPL-2.0-only WITH Classpath-exception-2.0"),
+        (spdx("(Apache-2.0 OR MIT) AND GPL-2.0-only WITH Classpath-exception-2.0"), LicenseKind::Free),
+    );
+    assert_eq!(normalize("MIT AND Foo"), (None, LicenseKind::Unknown));
+    assert_eq!(normalize("(MIT OR"), (None, LicenseKind::Unknown));
+}
+
+#[test]
+fn test_package_report() {
+    let search: Search<InfoResult> = serde_json::from_str(include_str!("fixtures/info.json")).unwrap();
+
+    let licenses = search.results[1].parsed_licenses();
+    assert_eq!(licenses.len(), 2);
+    assert_eq!(licenses[0].to_string(), "MIT");
+    assert_eq!(licenses[1].to_string(), "Apache-2.0");
+    assert_eq!(licenses[1].original, "Apache");
+    assert_eq!(license::classify(&licenses), LicenseKind::Free);
+
+    let mixed = vec![License::parse("MIT"), License::parse("custom:eula")];
+    assert_eq!(license::classify(&mixed), LicenseKind::NonFree);
+
+    let unknown = vec![License::parse("MIT"), License::parse("custom:Foo")];
+    assert_eq!(license::classify(&unknown), LicenseKind::Unknown);
+    assert_eq!(license::classify(&[]), LicenseKind::Unknown);
+}
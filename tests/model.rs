@@ -0,0 +1,275 @@
+extern crate aur;
+extern crate serde_json;
+
+use aur::{Endpoint, Error};
+use aur::model::{
+    InfoResult,
+    InfoResultRef,
+    Package,
+    ResponseType,
+    Search,
+    SearchResult,
+    SearchResultRef,
+};
+use std::borrow::Cow;
+use std::time::{Duration, UNIX_EPOCH};
+
+const INFO: &str = include_str!("fixtures/info.json");
+const SEARCH: &str = include_str!("fixtures/search.json");
+
+#[test]
+fn test_search_ref_borrows() {
+    let search: Search<SearchResultRef> = serde_json::from_str(SEARCH).unwrap();
+
+    assert_eq!(search.result_count, 3);
+
+    let result = &search.results[0];
+    assert_eq!(result.name, "yay-bin");
+
+    match result.name {
+        Cow::Borrowed(_) => {},
+        Cow::Owned(_) => panic!("name was not borrowed"),
+    }
+
+    match result.maintainer {
+        Some(Cow::Borrowed(maintainer)) => assert_eq!(maintainer, "jguer"),
+        ref other => panic!("maintainer was not borrowed: {:?}", other),
+    }
+
+    assert!(search.results[1].maintainer.is_none());
+}
+
+#[test]
+fn test_info_ref_borrows_lists() {
+    let search: Search<InfoResultRef> = serde_json::from_str(INFO).unwrap();
+
+    let yay = &search.results[0];
+    assert_eq!(yay.dependencies, vec!["pacman>5", "git"]);
+    assert!(yay.dependencies.iter().all(|dep| match *dep {
+        Cow::Borrowed(_) => true,
+        Cow::Owned(_) => false,
+    }));
+
+    // Descriptions containing escapes can't be borrowed and are allocated.
+    let rust = &search.results[1];
+    match rust.description {
+        Some(Cow::Owned(ref description)) => {
+            assert!(description.starts_with("The \"nightly\""));
+        },
+        ref other => panic!("description was borrowed: {:?}", other),
+    }
+}
+
+#[test]
+fn test_ref_into_owned_matches_owned() {
+    let borrowed: Search<InfoResultRef> = serde_json::from_str(INFO).unwrap();
+    let owned: Search<InfoResult> = serde_json::from_str(INFO).unwrap();
+
+    for (borrowed, owned) in borrowed.results.into_iter().zip(owned.results) {
+        let converted = InfoResult::from(borrowed);
+
+        assert_eq!(
+            serde_json::to_value(&converted).unwrap(),
+            serde_json::to_value(&owned).unwrap(),
+        );
+    }
+
+    let borrowed: Search<SearchResultRef> = serde_json::from_str(SEARCH).unwrap();
+    let owned: Search<SearchResult> = serde_json::from_str(SEARCH).unwrap();
+
+    for (borrowed, owned) in borrowed.results.into_iter().zip(owned.results) {
+        let converted = SearchResult::from(borrowed);
+
+        assert_eq!(
+            serde_json::to_value(&converted).unwrap(),
+            serde_json::to_value(&owned).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn test_info_all_fields() {
+    let search: Search<InfoResult> = serde_json::from_str(INFO).unwrap();
+
+    let yay = &search.results[0];
+    assert_eq!(yay.check_depends, vec!["go"]);
+    assert_eq!(yay.submitter.as_ref().map(|s| &s[..]), Some("jguer"));
+    assert!(yay.co_maintainers.is_empty());
+    assert!(yay.groups.is_empty());
+    assert!(yay.replaces.is_empty());
+
+    let rust = &search.results[1];
+    assert_eq!(rust.co_maintainers, vec!["heftig"]);
+    assert_eq!(rust.groups, vec!["rust"]);
+    assert_eq!(rust.replaces, vec!["rust-nightly-bin"]);
+    assert_eq!(rust.provides, vec!["rust", "cargo=1.77.0"]);
+    assert!(rust.check_depends.is_empty());
+    assert!(rust.keywords.is_empty());
+}
+
+#[test]
+fn test_info_absent_fields_default() {
+    let json = include_str!("fixtures/info_minimal.json");
+
+    let search: Search<InfoResult> = serde_json::from_str(json).unwrap();
+    let package = &search.results[0];
+
+    assert_eq!(package.name, "aursearch");
+    assert!(package.dependencies.is_empty());
+    assert!(package.keywords.is_empty());
+    assert!(package.license.is_empty());
+    assert!(package.submitter.is_none());
+    assert_eq!(package.out_of_date, Some(1312573217));
+
+    let search: Search<InfoResultRef> = serde_json::from_str(json).unwrap();
+    let package = &search.results[0];
+
+    assert!(package.dependencies.is_empty());
+    assert!(package.keywords.is_empty());
+    assert!(package.license.is_empty());
+    assert!(package.submitter.is_none());
+}
+
+#[test]
+fn test_absent_optional_fields() {
+    // `Description`, `Maintainer`, and `URL` are left out entirely rather than
+    // being null.
+    let json = include_str!("fixtures/info_no_optional.json");
+
+    let search: Search<InfoResult> = serde_json::from_str(json).unwrap();
+    let package = &search.results[0];
+    assert!(package.description.is_none() && package.maintainer.is_none() && package.url.is_none());
+
+    let search: Search<InfoResultRef> = serde_json::from_str(json).unwrap();
+    let package = &search.results[0];
+    assert!(package.description.is_none() && package.maintainer.is_none() && package.url.is_none());
+
+    let search: Search<SearchResult> = serde_json::from_str(json).unwrap();
+    let package = &search.results[0];
+    assert!(package.description.is_none() && package.maintainer.is_none() && package.url.is_none());
+
+    let search: Search<SearchResultRef> = serde_json::from_str(json).unwrap();
+    let package = &search.results[0];
+    assert!(package.description.is_none() && package.maintainer.is_none() && package.url.is_none());
+}
+
+#[test]
+fn test_timestamps() {
+    let search: Search<InfoResult> = serde_json::from_str(INFO).unwrap();
+    let rust = &search.results[1];
+
+    let epoch = UNIX_EPOCH;
+    assert_eq!(rust.first_submitted_time(), epoch + Duration::from_secs(1411416556));
+    assert_eq!(rust.last_modified_time(), epoch + Duration::from_secs(1700000000));
+    assert_eq!(rust.out_of_date_time(), Some(epoch + Duration::from_secs(1710000000)));
+
+    let now = epoch + Duration::from_secs(1710000000 + 86400);
+    assert_eq!(rust.out_of_date_duration_at(now), Some(Duration::from_secs(86400)));
+    assert_eq!(rust.time_since_update_at(now), Duration::from_secs(10000000 + 86400));
+
+    // A clock behind the package's timestamps saturates to zero.
+    assert_eq!(rust.time_since_update_at(epoch), Duration::from_secs(0));
+
+    let yay = &search.results[0];
+    assert!(yay.out_of_date_time().is_none());
+    assert!(yay.out_of_date_duration().is_none());
+
+    // The wire format is left untouched.
+    let value = serde_json::to_value(rust).unwrap();
+    assert_eq!(value["LastModified"], 1700000000);
+    assert_eq!(value["OutOfDate"], 1710000000);
+}
+
+#[test]
+fn test_urls() {
+    let search: Search<SearchResult> = serde_json::from_str(SEARCH).unwrap();
+    let package = &search.results[1];
+
+    let endpoint = Endpoint::default();
+    assert_eq!(package.snapshot_url(&endpoint), "https://aur.archlinux.org/cgit/aur.git/snapshot/yay-git.tar.gz");
+    assert_eq!(package.git_clone_url(&endpoint), "https://aur.archlinux.org/yay-git.git");
+    assert_eq!(package.web_url(&endpoint), "https://aur.archlinux.org/packages/yay-git");
+    assert_eq!(package.log_url(&endpoint), "https://aur.archlinux.org/cgit/aur.git/log/?h=yay-git");
+
+    let endpoint = Endpoint::new("http://localhost:8080/aur/");
+    assert_eq!(package.snapshot_url(&endpoint), "http://localhost:8080/aur/cgit/aur.git/snapshot/yay-git.tar.gz");
+    assert_eq!(package.git_clone_url(&endpoint), "http://localhost:8080/aur/yay-git.git");
+    assert_eq!(endpoint.snapshot_url("yay-git"), package.snapshot_url(&endpoint));
+}
+
+#[test]
+fn test_urls_encode_names() {
+    let endpoint = Endpoint::default();
+
+    assert_eq!(endpoint.snapshot_url("../x?y#z"), "https://aur.archlinux.org/cgit/aur.git/snapshot/..%2Fx%3Fy%23z.tar.gz");
+    assert_eq!(endpoint.clone_url("libc++"), "https://aur.archlinux.org/libc++.git");
+    assert_eq!(endpoint.package_url("a b"), "https://aur.archlinux.org/packages/a%20b");
+    assert_eq!(endpoint.log_url("libc++&h=x"), "https://aur.archlinux.org/cgit/aur.git/log/?h=libc%2B%2B%26h%3Dx");
+}
+
+#[test]
+fn test_package_trait() {
+    fn summary<P: Package>(package: &P) -> (String, u64, bool, bool) {
+        (package.name().to_owned(), package.num_votes(), package.is_orphaned(), package.is_out_of_date())
+    }
+
+    let info: Search<InfoResult> = serde_json::from_str(INFO).unwrap();
+    let info_ref: Search<InfoResultRef> = serde_json::from_str(INFO).unwrap();
+    let search: Search<SearchResult> = serde_json::from_str(SEARCH).unwrap();
+    let search_ref: Search<SearchResultRef> = serde_json::from_str(SEARCH).unwrap();
+
+    assert_eq!(summary(&info.results[1]), ("rust-nightly".to_owned(), 87, true, true));
+    assert_eq!(summary(&info_ref.results[1]), summary(&info.results[1]));
+    assert_eq!(summary(&search.results[0]), ("yay-bin".to_owned(), 502, false, false));
+    assert_eq!(summary(&search_ref.results[0]), summary(&search.results[0]));
+
+    assert_eq!(info_ref.results[0].url(), Some("https://github.com/Jguer/yay"));
+    assert_eq!(info.results[1].parsed_version().epoch(), Some("1"));
+}
+
+#[test]
+fn test_info_into_search_result() {
+    let info: Search<InfoResult> = serde_json::from_str(INFO).unwrap();
+    let search: Search<SearchResult> = serde_json::from_str(SEARCH).unwrap();
+
+    let yay = SearchResult::from(info.results[0].clone());
+
+    assert_eq!(
+        serde_json::to_value(&yay).unwrap(),
+        serde_json::to_value(&search.results[2]).unwrap(),
+    );
+}
+
+#[test]
+fn test_verify_response_type() {
+    let info: Search<InfoResult> = serde_json::from_str(INFO).unwrap();
+    assert_eq!(info.type_, ResponseType::Multiinfo);
+    assert!(info.clone().verify(ResponseType::Info).is_ok());
+
+    match info.verify(ResponseType::Search) {
+        Err(Error::UnexpectedResponseType(ResponseType::Search, ResponseType::Multiinfo)) => {},
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let search: Search<SearchResult> = serde_json::from_str(SEARCH).unwrap();
+    assert!(search.clone().verify(ResponseType::Search).is_ok());
+    assert!(search.verify(ResponseType::Info).is_err());
+}
+
+#[test]
+fn test_verify_error_response() {
+    let body = r#"{"error":"Query arg too small.","resultcount":0,"results":[],"type":"error","version":5}"#;
+    let search: Search<SearchResult> = serde_json::from_str(body).unwrap();
+
+    match search.verify(ResponseType::Search) {
+        Err(Error::Rpc(ref message)) => assert_eq!(message, "Query arg too small."),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_unknown_response_type_fails() {
+    let body = r#"{"resultcount":0,"results":[],"type":"bogus","version":5}"#;
+
+    assert!(serde_json::from_str::<Search<SearchResult>>(body).is_err());
+}
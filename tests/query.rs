@@ -0,0 +1,71 @@
+extern crate aur;
+#[cfg(feature = "regex")]
+extern crate regex;
+extern crate serde_json;
+
+use aur::model::{InfoResult, Package, Search, SearchResult};
+use aur::query::{Order, Query, SortKey};
+
+fn search() -> Search<SearchResult> {
+    serde_json::from_str(include_str!("fixtures/search.json")).unwrap()
+}
+
+fn names<P: Package>(packages: &[&P]) -> Vec<String> {
+    packages.iter().map(|package| package.name().to_owned()).collect()
+}
+
+#[test]
+fn test_empty_query_keeps_order() {
+    let search = search();
+
+    assert_eq!(names(&search.query(&Query::new())), vec!["yay-bin", "yay-git", "yay"]);
+}
+
+#[test]
+fn test_sort() {
+    let search = search();
+
+    let cases = vec![
+        (SortKey::Votes, Order::Descending, vec!["yay", "yay-bin", "yay-git"]),
+        (SortKey::Popularity, Order::Ascending, vec!["yay-git", "yay-bin", "yay"]),
+        (SortKey::Name, Order::Ascending, vec!["yay", "yay-bin", "yay-git"]),
+        (SortKey::LastModified, Order::Descending, vec!["yay-bin", "yay", "yay-git"]),
+        (SortKey::FirstSubmitted, Order::Ascending, vec!["yay", "yay-git", "yay-bin"]),
+    ];
+
+    for (key, order, expected) in cases {
+        let query = Query::new().sort_by(key, order);
+
+        assert_eq!(names(&search.query(&query)), expected, "{:?} {:?}", key, order);
+    }
+}
+
+#[test]
+fn test_filters() {
+    let search = search();
+
+    assert_eq!(names(&search.query(&Query::new().orphaned(true))), vec!["yay-git"]);
+    assert_eq!(names(&search.query(&Query::new().out_of_date(false))), vec!["yay-bin", "yay"]);
+    assert_eq!(names(&search.query(&Query::new().min_votes(500))), vec!["yay-bin", "yay"]);
+    assert_eq!(names(&search.query(&Query::new().maintainer("jguer").min_votes(1000))), vec!["yay"]);
+    assert!(search.query(&Query::new().maintainer("nobody")).is_empty());
+}
+
+#[test]
+fn test_info_results() {
+    let info: Search<InfoResult> = serde_json::from_str(include_str!("fixtures/info.json")).unwrap();
+    let query = Query::new().sort_by(SortKey::Popularity, Order::Descending);
+
+    assert_eq!(names(&info.query(&query)), vec!["yay", "rust-nightly"]);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_name_regex() {
+    let search = search();
+    let query = Query::new()
+        .name_regex(regex::Regex::new("-(bin|git)$").unwrap())
+        .sort_by(SortKey::Name, Order::Descending);
+
+    assert_eq!(names(&search.query(&query)), vec!["yay-git", "yay-bin"]);
+}
@@ -0,0 +1,226 @@
+extern crate aur;
+extern crate serde_json;
+
+mod common;
+
+use aur::base::Base;
+use aur::dependency::Dependency;
+use aur::model::{InfoResult, SearchResult};
+use aur::provider::{Candidate, FirstCandidate};
+use aur::resolve::{Missing, Resolver};
+use aur::Error;
+use std::cell::RefCell;
+
+fn search_provides(packages: &[InfoResult], name: &str) -> Vec<SearchResult> {
+    packages
+        .iter()
+        .filter(|package| {
+            package.parsed_provides().iter().any(|provision| provision.name == name)
+        })
+        .cloned()
+        .map(SearchResult::from)
+        .collect()
+}
+
+fn is_repo(dependency: &Dependency) -> bool {
+    dependency.name == "glibc" || dependency.name == "zlib"
+}
+
+fn bases(build_order: &[Base]) -> Vec<Vec<&str>> {
+    build_order
+        .iter()
+        .map(|base| base.packages().iter().map(|package| &package.name[..]).collect())
+        .collect()
+}
+
+#[test]
+fn test_build_order() {
+    let packages = common::packages("resolve.json");
+    let batches = RefCell::new(Vec::new());
+
+    let resolution = Resolver::new(|names| {
+        batches.borrow_mut().push(names.to_vec());
+
+        Ok(common::lookup(&packages, names))
+    }, is_repo).resolve(&["app", "nonexistent"]).unwrap();
+
+    assert_eq!(bases(&resolution.build_order), vec![
+        vec!["core"],
+        vec!["lib-a", "lib-b"],
+        vec!["tool"],
+        vec!["app"],
+    ]);
+    assert_eq!(*batches.borrow(), vec![
+        vec!["app".to_owned(), "nonexistent".to_owned()],
+        vec!["lib-a".to_owned(), "tool".to_owned()],
+        vec!["lib-b".to_owned()],
+        vec!["core".to_owned(), "ghost".to_owned()],
+    ]);
+    assert_eq!(resolution.missing, vec![
+        Missing {
+            dependency: Dependency::parse("nonexistent"),
+            required_by: vec![],
+        },
+        Missing {
+            dependency: Dependency::parse("core>=3"),
+            required_by: vec!["lib-b".to_owned()],
+        },
+        Missing {
+            dependency: Dependency::parse("ghost"),
+            required_by: vec!["lib-b".to_owned()],
+        },
+    ]);
+    assert_eq!(resolution.satisfied, vec![
+        Dependency::parse("glibc"),
+        Dependency::parse("zlib"),
+    ]);
+}
+
+#[test]
+fn test_options() {
+    let packages = common::packages("resolve.json");
+    let mut batches = 0;
+
+    let resolution = Resolver::new(|names| {
+        batches += 1;
+
+        Ok(common::lookup(&packages, names))
+    }, |dependency| is_repo(dependency) || dependency.name.starts_with("lib-"))
+        .batch_size(1)
+        .check_depends(true)
+        .make_depends(false)
+        .resolve(&["app", "lib-c"])
+        .unwrap();
+
+    assert_eq!(bases(&resolution.build_order), vec![
+        vec!["checker"],
+        vec!["app"],
+        vec!["lib-c"],
+    ]);
+    assert_eq!(batches, 3);
+    assert!(resolution.missing.is_empty());
+}
+
+#[test]
+fn test_split_targets() {
+    let packages = common::packages("resolve.json");
+    let mut batches = 0;
+
+    let resolution = Resolver::new(|names| {
+        batches += 1;
+
+        Ok(common::lookup(&packages, names))
+    }, is_repo).resolve(&["lib-a", "lib-c"]).unwrap();
+
+    assert_eq!(bases(&resolution.build_order), vec![
+        vec!["core"],
+        vec!["lib-a", "lib-c", "lib-b"],
+    ]);
+    assert_eq!(batches, 3);
+
+    let libs = &resolution.build_order[1];
+    assert_eq!(libs.name(), "libs");
+    assert!(libs.is_requested("lib-a"));
+    assert!(libs.is_requested("lib-c"));
+    assert!(!libs.is_requested("lib-b"));
+    assert_eq!(libs.dependencies(), vec![
+        Dependency::parse("zlib"),
+        Dependency::parse("core>=3"),
+        Dependency::parse("ghost"),
+    ]);
+    assert!(resolution.build_order[0].requested().is_empty());
+}
+
+#[test]
+fn test_cycle() {
+    let packages = common::packages("resolve.json");
+
+    let result = Resolver::new(|names| Ok(common::lookup(&packages, names)), is_repo)
+        .resolve(&["cycle-a"]);
+
+    match result {
+        Err(Error::DependencyCycle(path)) => {
+            assert_eq!(path, vec!["cycle-a", "cycle-b", "cycle-a"]);
+        },
+        other => panic!("Expected a dependency cycle: {:?}", other),
+    }
+}
+
+#[test]
+fn test_split_package_cycle() {
+    let packages = common::packages("resolve.json");
+
+    let result = Resolver::new(|names| Ok(common::lookup(&packages, names)), is_repo)
+        .resolve(&["split-x"]);
+
+    match result {
+        Err(Error::DependencyCycle(path)) => {
+            assert_eq!(path, vec!["split", "middle", "split"]);
+        },
+        other => panic!("Expected a dependency cycle: {:?}", other),
+    }
+}
+
+#[test]
+fn test_fetch_error() {
+    let result = Resolver::new(|_| Err(Error::Rpc("Too many requests".to_owned())), is_repo)
+        .resolve(&["app"]);
+
+    match result {
+        Err(Error::Rpc(message)) => assert_eq!(message, "Too many requests"),
+        other => panic!("Expected an RPC error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_providers() {
+    let packages = common::packages("resolve.json");
+    let searches = RefCell::new(Vec::new());
+
+    let resolution = Resolver::new(|names| Ok(common::lookup(&packages, names)), is_repo)
+        .providers(|name| {
+            searches.borrow_mut().push(name.to_owned());
+
+            Ok(search_provides(&packages, name))
+        }, FirstCandidate)
+        .resolve(&["java-app", "app"])
+        .unwrap();
+
+    assert_eq!(bases(&resolution.build_order), vec![
+        vec!["jdk17"],
+        vec!["java-app"],
+        vec!["core-git"],
+        vec!["lib-a", "lib-b"],
+        vec!["tool"],
+        vec!["app"],
+    ]);
+    assert_eq!(*searches.borrow(), vec!["java-runtime", "core", "ghost"]);
+    assert_eq!(resolution.missing, vec![Missing {
+        dependency: Dependency::parse("ghost"),
+        required_by: vec!["lib-b".to_owned()],
+    }]);
+}
+
+#[test]
+fn test_declined_provider() {
+    let packages = common::packages("resolve.json");
+    let mut offered = Vec::new();
+
+    let chooser = |_: &Dependency, candidates: &[Candidate]| {
+        offered.extend(candidates.iter().map(|candidate| candidate.package.name.clone()));
+
+        None
+    };
+
+    let resolution = Resolver::new(|names| Ok(common::lookup(&packages, names)), is_repo)
+        .providers(|name| Ok(search_provides(&packages, name)), chooser)
+        .resolve(&["java-app"])
+        .unwrap();
+
+    assert_eq!(bases(&resolution.build_order), vec![vec!["java-app"]]);
+    assert_eq!(resolution.missing, vec![Missing {
+        dependency: Dependency::parse("java-runtime>=17"),
+        required_by: vec!["java-app".to_owned()],
+    }]);
+    assert_eq!(offered, vec!["jdk17", "jdk17-orphan", "jdk21"]);
+}
@@ -0,0 +1,136 @@
+extern crate aur;
+extern crate flate2;
+extern crate tar;
+extern crate tempfile;
+
+use aur::{snapshot, Error};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tar::{Builder, EntryType, Header};
+
+/// Builds a gzipped tarball, writing entry names and link targets directly
+/// into the headers so that unsafe paths are not rejected while building.
+fn tarball(entries: &[(&str, EntryType, &str)]) -> Vec<u8> {
+    let mut builder = Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+
+    for &(path, entry_type, contents) in entries {
+        let mut header = Header::new_gnu();
+        header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+        header.set_entry_type(entry_type);
+        header.set_mode(0o644);
+
+        let data = match entry_type {
+            EntryType::Symlink | EntryType::Link => {
+                header.as_old_mut().linkname[..contents.len()]
+                    .copy_from_slice(contents.as_bytes());
+
+                &[][..]
+            },
+            _ => contents.as_bytes(),
+        };
+
+        header.set_size(data.len() as u64);
+        header.set_cksum();
+        builder.append(&header, data).unwrap();
+    }
+
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+fn assert_unsafe(entries: &[(&str, EntryType, &str)], entry: &str) {
+    let dir = tempfile::tempdir().unwrap();
+
+    match snapshot::extract(&tarball(entries)[..], dir.path().join("out")) {
+        Err(Error::UnsafeArchiveEntry(ref path)) => assert_eq!(path, Path::new(entry)),
+        other => panic!("{}: unexpected result {:?}", entry, other),
+    }
+}
+
+#[test]
+fn test_extract() {
+    let dir = tempfile::tempdir().unwrap();
+    let data = tarball(&[
+        ("pax_global_header", EntryType::XGlobalHeader, "52 comment=0123\n"),
+        ("yay/", EntryType::Directory, ""),
+        ("yay/.SRCINFO", EntryType::Regular, "pkgbase = yay\n"),
+        ("yay/PKGBUILD", EntryType::Regular, "pkgname=yay\n"),
+        ("yay/sub/", EntryType::Directory, ""),
+        ("yay/sub/link", EntryType::Symlink, "../PKGBUILD"),
+        ("yay/hard", EntryType::Link, "yay/PKGBUILD"),
+    ]);
+
+    snapshot::extract(&data[..], dir.path()).unwrap();
+
+    let root = dir.path().join("yay");
+    assert_eq!(fs::read_to_string(root.join("PKGBUILD")).unwrap(), "pkgname=yay\n");
+    assert_eq!(fs::read_to_string(root.join("sub/link")).unwrap(), "pkgname=yay\n");
+    assert_eq!(fs::read_to_string(root.join("hard")).unwrap(), "pkgname=yay\n");
+    assert!(!dir.path().join("pax_global_header").exists());
+}
+
+#[test]
+fn test_extract_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("yay.tar.gz");
+    fs::File::create(&path)
+        .unwrap()
+        .write_all(&tarball(&[("yay/PKGBUILD", EntryType::Regular, "pkgname=yay\n")]))
+        .unwrap();
+
+    snapshot::extract_file(&path, dir.path().join("out")).unwrap();
+
+    assert!(dir.path().join("out/yay/PKGBUILD").is_file());
+}
+
+#[test]
+fn test_reject_paths() {
+    assert_unsafe(&[("/etc/passwd", EntryType::Regular, "")], "/etc/passwd");
+    assert_unsafe(&[("yay/../../escape", EntryType::Regular, "")], "yay/../../escape");
+    assert_unsafe(&[("../escape", EntryType::Directory, "")], "../escape");
+}
+
+#[test]
+fn test_reject_special_files() {
+    assert_unsafe(&[("yay/null", EntryType::Char, "")], "yay/null");
+    assert_unsafe(&[("yay/sda", EntryType::Block, "")], "yay/sda");
+    assert_unsafe(&[("yay/fifo", EntryType::Fifo, "")], "yay/fifo");
+}
+
+#[test]
+fn test_reject_links() {
+    assert_unsafe(&[("yay/link", EntryType::Symlink, "/etc/passwd")], "yay/link");
+    assert_unsafe(&[("yay/link", EntryType::Symlink, "../../escape")], "yay/link");
+    assert_unsafe(&[("yay/hard", EntryType::Link, "../escape")], "yay/hard");
+    assert_unsafe(&[("yay/hard", EntryType::Link, "/etc/passwd")], "yay/hard");
+}
+
+#[test]
+fn test_reject_links_through_links() {
+    // Lexically `here/../escape` stays within the destination, but `here`
+    // resolves to the root so its parent is outside of it.
+    assert_unsafe(&[
+        ("here", EntryType::Symlink, "."),
+        ("link", EntryType::Symlink, "here/../escape"),
+    ], "link");
+    assert_unsafe(&[
+        ("up", EntryType::Symlink, "."),
+        ("up/../escape", EntryType::Regular, ""),
+    ], "up/../escape");
+}
+
+#[test]
+fn test_reject_links_through_later_links() {
+    // When `link` is extracted `x` does not exist yet, so where `x/..` leads
+    // is only decided by the later entry turning `x` into a link to the root.
+    assert_unsafe(&[
+        ("link", EntryType::Symlink, "x/../secret"),
+        ("x", EntryType::Symlink, "."),
+    ], "link");
+    assert_unsafe(&[
+        ("yay/file", EntryType::Regular, ""),
+        ("yay/link", EntryType::Symlink, "file/../PKGBUILD"),
+    ], "yay/link");
+}
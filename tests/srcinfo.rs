@@ -0,0 +1,196 @@
+extern crate aur;
+
+use aur::srcinfo::{self, ArchValues, PackageBase, ParseError, ParseErrorKind, SplitPackage, Srcinfo};
+use aur::Error;
+
+fn libfoo() -> Srcinfo {
+    Srcinfo::parse(include_str!("fixtures/srcinfo/libfoo.SRCINFO")).unwrap()
+}
+
+fn arch_values(arch: Option<&str>, values: &[&str]) -> ArchValues {
+    ArchValues {
+        arch: arch.map(ToOwned::to_owned),
+        values: values.iter().map(|value| value.to_string()).collect(),
+    }
+}
+
+fn parse_error(text: &str) -> ParseError {
+    match Srcinfo::parse(text) {
+        Err(Error::Srcinfo(why)) => why,
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_base() {
+    let srcinfo = Srcinfo::parse(include_str!("fixtures/srcinfo/yay.SRCINFO")).unwrap();
+    let base = &srcinfo.base;
+
+    assert_eq!(base.name, "yay");
+    assert_eq!(base.pkgver, "12.4.2");
+    assert_eq!(base.pkgrel, "1");
+    assert_eq!(base.epoch, None);
+    assert_eq!(base.version().as_str(), "12.4.2-1");
+    assert_eq!(base.arch.len(), 8);
+    assert_eq!(base.depends, vec![arch_values(None, &["pacman>6.1", "git"])]);
+    assert_eq!(base.opt_depends[0].values[1], "doas: privilege elevation");
+    assert_eq!(base.options, vec!["!lto", "!debug"]);
+    assert_eq!(base.sha256_sums[0].values.len(), 1);
+    assert_eq!(srcinfo.names(), vec!["yay"]);
+}
+
+#[test]
+fn test_parse_arch_specific() {
+    let srcinfo = libfoo();
+    let base = &srcinfo.base;
+
+    assert_eq!(base.version().as_str(), "1:2.1.0-3");
+    assert_eq!(base.depends, vec![
+        arch_values(None, &["glibc"]),
+        arch_values(Some("x86_64"), &["lib32-glibc"]),
+        arch_values(Some("aarch64"), &["libatomic"]),
+    ]);
+    assert_eq!(srcinfo::for_arch(&base.source, "aarch64"), vec![
+        "https://example.org/libfoo-2.1.0.tar.xz",
+        "https://example.org/libfoo-2.1.0.tar.xz.sig",
+        "https://example.org/blob-aarch64.bin",
+    ]);
+    assert_eq!(srcinfo::for_arch(&base.sha256_sums, "x86_64").len(), 3);
+}
+
+#[test]
+fn test_parse_overrides() {
+    let srcinfo = libfoo();
+    assert_eq!(srcinfo.names(), vec!["libfoo", "python-libfoo", "libfoo-docs"]);
+
+    let python = srcinfo.package("python-libfoo").unwrap();
+    assert_eq!(python.description.as_ref().unwrap(), "Python bindings for libfoo");
+    assert_eq!(python.install.as_ref().unwrap(), "");
+    assert_eq!(python.url, None);
+    assert_eq!(python.arch, None);
+    assert_eq!(python.depends, vec![
+        arch_values(None, &["libfoo", "python"]),
+        arch_values(Some("x86_64"), &[]),
+    ]);
+
+    let docs = srcinfo.package("libfoo-docs").unwrap();
+    assert_eq!(docs.arch, Some(vec!["any".to_owned()]));
+    assert_eq!(docs.groups, None);
+    assert_eq!(docs.provides, vec![arch_values(None, &[])]);
+}
+
+#[test]
+fn test_resolve() {
+    let srcinfo = libfoo();
+
+    let libfoo = srcinfo.resolve("libfoo").unwrap();
+    assert_eq!(libfoo.package_base, "libfoo");
+    assert_eq!(libfoo.description.as_ref().unwrap(), "A library for foo");
+    assert_eq!(libfoo.install.as_ref().unwrap(), "libfoo.install");
+    assert_eq!(libfoo.version().as_str(), "1:2.1.0-3");
+    assert_eq!(srcinfo::for_arch(&libfoo.depends, "x86_64"), vec!["glibc", "zlib", "lib32-glibc"]);
+    assert_eq!(srcinfo::for_arch(&libfoo.make_depends, "x86_64"), vec!["cmake", "python"]);
+
+    let python = srcinfo.resolve("python-libfoo").unwrap();
+    assert_eq!(python.install, None);
+    assert_eq!(python.url.as_ref().unwrap(), "https://example.org/libfoo");
+    assert_eq!(srcinfo::for_arch(&python.depends, "x86_64"), vec!["libfoo", "python"]);
+    assert_eq!(srcinfo::for_arch(&python.depends, "aarch64"), vec!["libfoo", "python", "libatomic"]);
+    assert_eq!(python.provides, vec![arch_values(None, &["foo"])]);
+
+    let docs = srcinfo.resolve("libfoo-docs").unwrap();
+    assert_eq!(docs.arch, vec!["any"]);
+    assert_eq!(docs.license, vec!["MIT", "CC-BY-SA-4.0"]);
+    assert!(docs.depends.is_empty());
+    assert!(docs.provides.is_empty());
+    assert!(docs.conflicts.is_empty());
+    assert_eq!(docs.check_depends, vec![arch_values(None, &["python-pytest"])]);
+
+    assert!(srcinfo.resolve("missing").is_none());
+}
+
+#[test]
+fn test_parse_errors() {
+    let cases = vec![
+        ("pkgver = 1\n", 1, ParseErrorKind::MissingKey("pkgbase")),
+        ("", 1, ParseErrorKind::MissingKey("pkgbase")),
+        ("pkgbase = a\n\tpkgrel = 1\n\npkgname = a\n", 1, ParseErrorKind::MissingKey("pkgver")),
+        ("pkgbase = a\n\tpkgver = 1\n\tpkgrel = 1\n", 1, ParseErrorKind::MissingKey("pkgname")),
+        ("pkgbase = a\n\tpkgver\n", 2, ParseErrorKind::MissingEquals),
+        ("pkgbase = a\n\tpkgver = 1\n\tpkgver = 2\n", 3, ParseErrorKind::DuplicateKey("pkgver".to_owned())),
+        ("pkgbase = a\n\tpkgdesc = a\n\tpkgdesc = b\n", 3, ParseErrorKind::DuplicateKey("pkgdesc".to_owned())),
+        ("pkgbase = a\n\tpkgver = \n", 2, ParseErrorKind::EmptyValue("pkgver".to_owned())),
+        ("pkgbase = a\n\tfoo = bar\n", 2, ParseErrorKind::UnknownKey("foo".to_owned())),
+        ("pkgbase = a\n\tlicense_x86_64 = MIT\n", 2, ParseErrorKind::UnknownKey("license_x86_64".to_owned())),
+        ("pkgbase = a\n\npkgname = a\n\tmakedepends = b\n", 4, ParseErrorKind::UnknownKey("makedepends".to_owned())),
+        ("pkgbase = a\n\npkgname = a\n\npkgname = a\n", 5, ParseErrorKind::DuplicatePackage("a".to_owned())),
+    ];
+
+    for (text, line, kind) in cases {
+        assert_eq!(parse_error(text), ParseError { kind, line }, "{:?}", text);
+    }
+
+    assert_eq!(parse_error("pkgbase = a\n\tfoo = bar\n").to_string(), "line 2: unknown key foo");
+}
+
+#[test]
+fn test_round_trip() {
+    let corpus = [
+        include_str!("fixtures/srcinfo/libfoo.SRCINFO"),
+        include_str!("fixtures/srcinfo/neovim-git.SRCINFO"),
+        include_str!("fixtures/srcinfo/nvidia-470xx-utils.SRCINFO"),
+        include_str!("fixtures/srcinfo/paru.SRCINFO"),
+        include_str!("fixtures/srcinfo/visual-studio-code-bin.SRCINFO"),
+        include_str!("fixtures/srcinfo/yay.SRCINFO"),
+    ];
+
+    for text in corpus.iter() {
+        let srcinfo = Srcinfo::parse(text).unwrap();
+
+        assert_eq!(srcinfo.to_string(), *text);
+        assert_eq!(Srcinfo::parse(&srcinfo.to_string()).unwrap(), srcinfo);
+    }
+}
+
+#[test]
+fn test_write_canonical_order() {
+    let mut base = PackageBase {
+        name: "example".to_owned(),
+        pkgver: "1.0".to_owned(),
+        pkgrel: "1".to_owned(),
+        arch: vec!["x86_64".to_owned()],
+        ..PackageBase::default()
+    };
+    base.sha256_sums.push(arch_values(Some("x86_64"), &["SKIP"]));
+    base.source.push(arch_values(Some("x86_64"), &["blob"]));
+    base.depends.push(arch_values(Some("riscv64"), &["extra"]));
+    base.depends.push(arch_values(None, &["glibc"]));
+    base.description = Some("An example".to_owned());
+
+    let srcinfo = Srcinfo {
+        base,
+        packages: vec![SplitPackage {
+            name: "example".to_owned(),
+            url: Some(String::new()),
+            depends: vec![arch_values(None, &[])],
+            ..SplitPackage::default()
+        }],
+    };
+
+    assert_eq!(srcinfo.to_string(), "\
+pkgbase = example
+\tpkgdesc = An example
+\tpkgver = 1.0
+\tpkgrel = 1
+\tarch = x86_64
+\tdepends = glibc
+\tsource_x86_64 = blob
+\tsha256sums_x86_64 = SKIP
+\tdepends_riscv64 = extra
+
+pkgname = example
+\turl = 
+\tdepends = 
+
+");
+}
@@ -0,0 +1,82 @@
+extern crate aur;
+extern crate serde_json;
+
+mod common;
+
+use aur::model::{InfoResult, SearchResult};
+use aur::upgrade::{Upgrade, UpgradeChecker};
+use std::cell::RefCell;
+
+fn search_replaces(packages: &[InfoResult], name: &str) -> Vec<SearchResult> {
+    packages
+        .iter()
+        .filter(|package| package.parsed_replaces().iter().any(|replaced| replaced.name == name))
+        .cloned()
+        .map(SearchResult::from)
+        .collect()
+}
+
+fn summary(upgrades: &[Upgrade]) -> Vec<(&str, &str, &str, &str)> {
+    upgrades.iter().map(|upgrade| {
+        (
+            &upgrade.name[..],
+            &upgrade.local_version[..],
+            &upgrade.package.name[..],
+            &upgrade.package.version[..],
+        )
+    }).collect()
+}
+
+const INSTALLED: &[(&str, &str)] = &[
+    ("yay", "12.3.5-1"),
+    ("paru", "1.11.2-1"),
+    ("epochpkg", "2.0-1"),
+    ("rc", "1.0-1"),
+    ("oldname", "1.5-1"),
+    ("gone", "4-1"),
+    ("ignored", "1-1"),
+];
+
+#[test]
+fn test_check() {
+    let packages = common::packages("upgrade.json");
+    let batches = RefCell::new(Vec::new());
+
+    let report = UpgradeChecker::new(|names| {
+        batches.borrow_mut().push(names.len());
+
+        Ok(common::lookup(&packages, names))
+    })
+        .batch_size(4)
+        .ignore(["ignored"])
+        .replacements(|name| Ok(search_replaces(&packages, name)))
+        .check(INSTALLED)
+        .unwrap();
+
+    assert_eq!(report.ignored, vec!["ignored"]);
+    assert_eq!(report.up_to_date, vec!["yay"]);
+    assert_eq!(summary(&report.upgrades), vec![
+        ("paru", "1.11.2-1", "paru", "2.0.4-1"),
+        ("epochpkg", "2.0-1", "epochpkg", "1:1.0-1"),
+    ]);
+    assert_eq!(summary(&report.local_newer), vec![("rc", "1.0-1", "rc", "1.0rc1-1")]);
+    assert_eq!(summary(&report.replaced), vec![("oldname", "1.5-1", "newname", "2.0-1")]);
+    assert_eq!(report.missing, vec!["gone"]);
+
+    // Two batches for the installed packages, then one for the replacements
+    // of each missing package.
+    assert_eq!(*batches.borrow(), vec![4, 2, 2, 1]);
+}
+
+#[test]
+fn test_without_replacements() {
+    let packages = common::packages("upgrade.json");
+
+    let report = UpgradeChecker::new(|names| Ok(common::lookup(&packages, names)))
+        .check(INSTALLED)
+        .unwrap();
+
+    assert!(report.ignored.is_empty());
+    assert!(report.replaced.is_empty());
+    assert_eq!(report.missing, vec!["oldname", "gone", "ignored"]);
+}
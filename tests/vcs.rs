@@ -0,0 +1,141 @@
+extern crate aur;
+extern crate serde_json;
+extern crate tempfile;
+
+mod common;
+
+use aur::srcinfo::Srcinfo;
+use aur::vcs::{self, Fragment, Source, Vcs};
+use aur::Error;
+use std::path::Path;
+
+fn source(repo: &Path, fragment: &str) -> Source {
+    Source::parse(&format!("git+file://{}{}", repo.display(), fragment)).unwrap()
+}
+
+#[test]
+fn test_from_name() {
+    assert_eq!(Vcs::from_name("yay-git"), Some(Vcs::Git));
+    assert_eq!(Vcs::from_name("foo-svn"), Some(Vcs::Svn));
+    assert_eq!(Vcs::from_name("foo-hg"), Some(Vcs::Hg));
+    assert_eq!(Vcs::from_name("foo-bzr"), Some(Vcs::Bzr));
+    assert_eq!(Vcs::from_name("foo-cvs"), Some(Vcs::Cvs));
+    assert_eq!(Vcs::from_name("foo-darcs"), Some(Vcs::Darcs));
+    assert_eq!(Vcs::from_name("foo-fossil"), Some(Vcs::Fossil));
+    assert_eq!(Vcs::from_name("legit"), None);
+    assert_eq!(Vcs::from_name("git"), None);
+    assert_eq!(Vcs::from_name("yay"), None);
+    assert_eq!(Vcs::Hg.to_string(), "hg");
+}
+
+#[test]
+fn test_parse_source() {
+    let source = Source::parse("git://git.example.org/foo.git").unwrap();
+    assert_eq!(source.vcs, Vcs::Git);
+    assert_eq!(source.url, "git://git.example.org/foo.git");
+    assert_eq!(source.name, None);
+    assert_eq!(source.fragment, None);
+    assert!(!source.signed);
+
+    let source = Source::parse("hg+https://hg.example.org/foo#branch=stable").unwrap();
+    assert_eq!(source.vcs, Vcs::Hg);
+    assert_eq!(source.url, "https://hg.example.org/foo");
+    assert_eq!(source.fragment, Some(Fragment::Branch("stable".to_owned())));
+
+    let source = Source::parse("foo::git+ssh://git@example.org/foo.git?signed").unwrap();
+    assert_eq!(source.name.as_ref().unwrap(), "foo");
+    assert_eq!(source.url, "ssh://git@example.org/foo.git");
+    assert_eq!(source.fragment, None);
+    assert!(source.signed);
+
+    let source = Source::parse("fossil+https://fossil.example.org/foo#commit=abc123").unwrap();
+    assert_eq!(source.vcs, Vcs::Fossil);
+    assert_eq!(source.fragment, Some(Fragment::Commit("abc123".to_owned())));
+
+    assert!(Source::parse("foo-1.0.tar.gz::https://example.org/foo-1.0.tar.gz").is_none());
+    assert!(Source::parse("foo.patch").is_none());
+    assert!(Source::parse("git+https://example.org/foo.git#ref=main").is_none());
+    assert!(Source::parse("cvs+https://example.org/foo").is_none());
+}
+
+#[test]
+fn test_srcinfo_sources() {
+    let srcinfo = Srcinfo::parse(include_str!("fixtures/srcinfo/mesa-tip.SRCINFO")).unwrap();
+    let sources = vcs::sources(&srcinfo)
+        .into_iter()
+        .map(|source| (source.vcs, source.url, source.fragment))
+        .collect::<Vec<_>>();
+
+    assert_eq!(sources, vec![
+        (
+            Vcs::Git,
+            "https://gitlab.freedesktop.org/mesa/mesa.git".to_owned(),
+            Some(Fragment::Branch("main".to_owned())),
+        ),
+        (
+            Vcs::Git,
+            "https://gitlab.freedesktop.org/mesa/drm.git".to_owned(),
+            Some(Fragment::Tag("libdrm-2.4.123".to_owned())),
+        ),
+        (
+            Vcs::Svn,
+            "https://svn.example.org/firmware/trunk".to_owned(),
+            Some(Fragment::Revision("1234".to_owned())),
+        ),
+    ]);
+}
+
+#[test]
+fn test_detect() {
+    let mesa = Srcinfo::parse(include_str!("fixtures/srcinfo/mesa-tip.SRCINFO")).unwrap();
+    let neovim = Srcinfo::parse(include_str!("fixtures/srcinfo/neovim-git.SRCINFO")).unwrap();
+    let yay = Srcinfo::parse(include_str!("fixtures/srcinfo/yay.SRCINFO")).unwrap();
+
+    assert_eq!(vcs::detect("mesa-tip", &mesa), Some(Vcs::Git));
+    assert_eq!(vcs::detect("neovim-git", &neovim), Some(Vcs::Git));
+    assert_eq!(vcs::detect("yay", &yay), None);
+    assert_eq!(vcs::detect("yay-hg", &yay), Some(Vcs::Hg));
+}
+
+#[test]
+fn test_upstream_commit() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path();
+
+    common::git(repo, &["init", "--quiet"]);
+    let first = common::commit(repo, "README", "first");
+    common::git(repo, &["tag", "-a", "-m", "v1", "v1"]);
+    common::git(repo, &["tag", "light"]);
+    let second = common::commit(repo, "README", "second");
+    common::git(repo, &["checkout", "--quiet", "-b", "dev"]);
+    let third = common::commit(repo, "README", "third");
+
+    assert_eq!(source(repo, "").upstream_commit().unwrap(), Some(third.clone()));
+    assert_eq!(source(repo, "#branch=dev").upstream_commit().unwrap(), Some(third));
+    assert_eq!(source(repo, "#tag=v1").upstream_commit().unwrap(), Some(first.clone()));
+    assert_eq!(source(repo, "#tag=light").upstream_commit().unwrap(), Some(first));
+    assert_eq!(source(repo, "#commit=abc123").upstream_commit().unwrap(), Some("abc123".to_owned()));
+
+    common::git(repo, &["checkout", "--quiet", "-"]);
+    assert_eq!(source(repo, "").upstream_commit().unwrap(), Some(second));
+
+    match source(repo, "#branch=missing").upstream_commit() {
+        Err(Error::Git(message)) => {
+            assert_eq!(message, "couldn't find remote ref refs/heads/missing");
+        },
+        other => panic!("Expected a git error: {:?}", other),
+    }
+
+    let svn = Source::parse(&format!("svn+file://{}", repo.display())).unwrap();
+    assert_eq!(svn.upstream_commit().unwrap(), None);
+}
+
+#[test]
+fn test_upstream_commit_missing_repository() {
+    let dir = tempfile::tempdir().unwrap();
+
+    match source(&dir.path().join("missing"), "").upstream_commit() {
+        Err(Error::Git(message)) => assert!(!message.is_empty()),
+        other => panic!("Expected a git error: {:?}", other),
+    }
+}
@@ -0,0 +1,193 @@
+extern crate aur;
+
+use aur::dependency::Dependency;
+use aur::version::{vercmp, Version};
+use std::cmp::Ordering;
+
+/// Version comparisons from pacman's own `vercmp` test suite, and from the
+/// rpm `rpmvercmp` tests that pacman's algorithm is derived from, with the
+/// expected result of comparing the left version to the right.
+const VERCMP: &[(&str, &str, i8)] = &[
+    // all similar length, no pkgrel
+    ("1.5.0", "1.5.0", 0),
+    ("1.5.1", "1.5.0", 1),
+    // mixed length
+    ("1.5.1", "1.5", 1),
+    // with pkgrel, simple
+    ("1.5.0-1", "1.5.0-1", 0),
+    ("1.5.0-1", "1.5.0-2", -1),
+    ("1.5.0-1", "1.5.1-1", -1),
+    ("1.5.0-2", "1.5.1-1", -1),
+    // with pkgrel, mixed lengths
+    ("1.5-1", "1.5.1-1", -1),
+    ("1.5-2", "1.5.1-1", -1),
+    ("1.5-2", "1.5.1-2", -1),
+    // mixed pkgrel inclusion
+    ("1.5", "1.5-1", 0),
+    ("1.5-1", "1.5", 0),
+    ("1.1-1", "1.1", 0),
+    ("1.0-1", "1.1", -1),
+    ("1.1-1", "1.0", 1),
+    // alphanumeric versions
+    ("1.5b-1", "1.5-1", -1),
+    ("1.5b", "1.5", -1),
+    ("1.5b-1", "1.5", -1),
+    ("1.5b", "1.5.1", -1),
+    // from the manpage
+    ("1.0a", "1.0alpha", -1),
+    ("1.0alpha", "1.0b", -1),
+    ("1.0b", "1.0beta", -1),
+    ("1.0beta", "1.0rc", -1),
+    ("1.0rc", "1.0", -1),
+    // going crazy? alpha-dotted versions
+    ("1.5.a", "1.5", 1),
+    ("1.5.b", "1.5.a", 1),
+    ("1.5.1", "1.5.b", 1),
+    // alpha dots and dashes
+    ("1.5.b-1", "1.5.b", 0),
+    ("1.5-1", "1.5.b", -1),
+    // same/similar content, differing separators
+    ("2.0", "2_0", 0),
+    ("2.0_a", "2_0.a", 0),
+    ("2.0a", "2.0.a", -1),
+    ("2___a", "2_a", 1),
+    // epoch included version comparisons
+    ("0:1.0", "0:1.0", 0),
+    ("0:1.0", "0:1.1", -1),
+    ("1:1.0", "0:1.0", 1),
+    ("1:1.0", "0:1.1", 1),
+    ("1:1.0", "2:1.1", -1),
+    // epoch + sometimes present pkgrel
+    ("1:1.0", "0:1.0-1", 1),
+    ("1:1.0-1", "0:1.1-1", 1),
+    // epoch included on one version
+    ("0:1.0", "1.0", 0),
+    ("0:1.0", "1.1", -1),
+    ("0:1.1", "1.0", 1),
+    ("1:1.0", "1.0", 1),
+    ("1:1.0", "1.1", 1),
+    ("1:1.1", "1.1", 1),
+    // only the epoch differs
+    ("1:1.0-1", "2:1.0-1", -1),
+    ("2:1.0-1", "1:1.0-1", 1),
+    ("10:1.0", "9:1.0", 1),
+    ("1:0.1", "0:99.9", 1),
+    // only the pkgrel differs
+    ("1:1.0-1", "1:1.0-2", -1),
+    ("1:1.0-10", "1:1.0-9", 1),
+    ("1.0-1", "1.0-01", 0),
+    ("1:1.0", "1:1.0-1", 0),
+    // alpha versus numeric segments
+    ("1.0.a", "1.0.1", -1),
+    ("1.a", "1.1", -1),
+    ("1a", "1.1", -1),
+    ("a", "1", -1),
+    ("xyz.4", "8", -1),
+    ("xyz.4", "2", -1),
+    ("1.0.1", "1.0a", 1),
+    ("2.0.1a", "2.0.1", -1),
+    ("6.0.rc1", "6.0", 1),
+    ("10xyz", "10.1xyz", -1),
+    ("xyz10", "xyz10.1", -1),
+    ("5.5p1", "5.5p2", -1),
+    ("5.5p1", "5.5p10", -1),
+    ("5.5p2", "5.6p1", -1),
+    ("10b2", "10a1", 1),
+    ("10a2", "10b2", -1),
+    ("1.0a", "1.0aa", -1),
+    // leading zeros and long numbers
+    ("10.0001", "10.1", 0),
+    ("10.0001", "10.0039", -1),
+    ("4.999.9", "5.0", -1),
+    ("20101121", "20101122", -1),
+    // pkgrel with decimals
+    ("1.0-1", "1.0-1.1", -1),
+    ("1.0-1.1", "1.0-1.2", -1),
+    ("1.0-2", "1.0-1.5", 1),
+    ("1.0-1.10", "1.0-1.9", 1),
+];
+
+fn ordering(expected: i8) -> Ordering {
+    match expected {
+        -1 => Ordering::Less,
+        0 => Ordering::Equal,
+        _ => Ordering::Greater,
+    }
+}
+
+#[test]
+fn test_vercmp_table() {
+    for &(left, right, expected) in VERCMP {
+        assert_eq!(vercmp(left, right), ordering(expected), "{} vs {}", left, right);
+        // Like pacman's test suite, every comparison is also checked reversed.
+        assert_eq!(vercmp(right, left), ordering(-expected), "{} vs {}", right, left);
+    }
+}
+
+#[test]
+fn test_total_order() {
+    let mut versions = ["1.0-2", "1.0", "1:0.1", "1.0-1", "0.9-3", "1.0-1.1"]
+        .iter()
+        .map(|version| Version::parse(version))
+        .collect::<Vec<_>>();
+    versions.sort();
+
+    let sorted = versions.iter().map(Version::as_str).collect::<Vec<_>>();
+    assert_eq!(sorted, ["0.9-3", "1.0", "1.0-1", "1.0-1.1", "1.0-2", "1:0.1"]);
+
+    // A missing pkgrel is older than any pkgrel, so equality is transitive.
+    assert!(Version::parse("1.0") < Version::parse("1.0-1"));
+    assert_ne!(Version::parse("1.0-1"), Version::parse("1.0"));
+    assert_eq!(Version::parse("1.0-1"), Version::parse("1.0-01"));
+
+    // Comparing as pacman does still lets a missing pkgrel match any pkgrel.
+    assert_eq!(Version::parse("1.0-1").vercmp(&Version::parse("1.0")), Ordering::Equal);
+    assert_eq!(Version::parse("1.0").vercmp(&Version::parse("1.0-2")), Ordering::Equal);
+    assert_eq!(Version::parse("1.0-1").vercmp(&Version::parse("1.0-2")), Ordering::Less);
+}
+
+#[test]
+fn test_parse_parts() {
+    let version = Version::parse("2:1.0.r12.gabc-3.1");
+
+    assert_eq!(version.epoch(), Some("2"));
+    assert_eq!(version.pkgver(), "1.0.r12.gabc");
+    assert_eq!(version.pkgrel(), Some("3.1"));
+    assert_eq!(version.to_string(), "2:1.0.r12.gabc-3.1");
+
+    let version = Version::parse("1.0");
+
+    assert_eq!(version.epoch(), None);
+    assert_eq!(version.pkgver(), "1.0");
+    assert_eq!(version.pkgrel(), None);
+}
+
+#[test]
+fn test_empty_epoch_is_zero() {
+    assert_eq!(vercmp(":1.0", "0:1.0"), Ordering::Equal);
+    assert_eq!(vercmp(":1.0", "1:1.0"), Ordering::Less);
+}
+
+#[test]
+fn test_constraints() {
+    let cases = &[
+        ("foo<1.0", "0.9-1", true),
+        ("foo<1.0", "1.0-1", false),
+        ("foo<=1.0", "1.0-1", true),
+        ("foo=1.0", "1.0-5", true),
+        ("foo=1.0-1", "1.0-2", false),
+        ("foo>=1:1.0", "2.0", false),
+        ("foo>=1:1.0", "1:1.0-1", true),
+        ("foo>1.0", "1.0.1-1", true),
+        ("foo>1.0", "1.0rc1-1", false),
+        ("foo", "0.1", true),
+    ];
+
+    for &(dependency, version, expected) in cases {
+        let dependency = Dependency::parse(dependency);
+        let version = Version::parse(version);
+
+        assert_eq!(dependency.is_satisfied_by("foo", &version), expected, "{} with {}", dependency, version);
+        assert!(!dependency.is_satisfied_by("bar", &version));
+    }
+}